@@ -0,0 +1,66 @@
+//! Stand-in for a remote signer: serves a local keystore to nodes started with
+//! `--keystore-uri unix://<socket>`.
+
+use clap::Parser;
+use sc_keystore::LocalKeystore;
+use sp_core::crypto::SecretString;
+use std::{fs, os::unix::net::UnixListener, path::PathBuf, sync::Arc};
+
+#[derive(Debug, Parser)]
+struct Opts {
+	/// Path of the Unix socket to listen on.
+	#[clap(long, parse(from_os_str))]
+	socket: PathBuf,
+
+	/// Directory holding the keys. Keys are only kept in memory when not given.
+	#[clap(long, parse(from_os_str))]
+	keystore_path: Option<PathBuf>,
+
+	/// File holding the password the keys in `--keystore-path` are encrypted with.
+	#[clap(long, parse(from_os_str), conflicts_with = "password-interactive")]
+	password_filename: Option<PathBuf>,
+
+	/// Prompt for the password the keys in `--keystore-path` are encrypted with.
+	#[clap(long)]
+	password_interactive: bool,
+}
+
+impl Opts {
+	/// The keystore password, which is never taken on the command line so that it stays out of
+	/// the process list and the shell history.
+	fn password(&self) -> Result<Option<SecretString>, String> {
+		let password = if let Some(path) = &self.password_filename {
+			let password = fs::read_to_string(path)
+				.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+			// Files usually end with a newline that is not part of the password.
+			password.trim_end_matches(&['\r', '\n'][..]).to_string()
+		} else if self.password_interactive {
+			rpassword::read_password_from_tty(Some("Key password: "))
+				.map_err(|e| format!("Failed to read the password: {}", e))?
+		} else {
+			return Ok(None)
+		};
+		Ok(Some(SecretString::new(password)))
+	}
+}
+
+fn main() -> Result<(), String> {
+	let opts = Opts::parse();
+
+	let keystore = match &opts.keystore_path {
+		Some(path) => LocalKeystore::open(path, opts.password()?)
+			.map_err(|e| format!("Failed to open keystore: {}", e))?,
+		None => LocalKeystore::in_memory(),
+	};
+
+	// A socket left behind by a previous run would make `bind` fail.
+	if opts.socket.exists() {
+		fs::remove_file(&opts.socket)
+			.map_err(|e| format!("Failed to remove {}: {}", opts.socket.display(), e))?;
+	}
+	let listener = UnixListener::bind(&opts.socket)
+		.map_err(|e| format!("Failed to listen on {}: {}", opts.socket.display(), e))?;
+	println!("Serving keystore on unix://{}", opts.socket.display());
+
+	node_template::keystore_server::serve(Arc::new(keystore), listener).map_err(|e| e.to_string())
+}
@@ -1,16 +1,26 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	opaque::SessionKeys, AccountId, AuraConfig, Balance, BalancesConfig, CouncilConfig,
+	GenesisConfig, GrandpaConfig, SessionConfig, Signature, SystemConfig, TemplateModuleConfig,
+	Treasury, ValidatorSetConfig, VestingConfig, SS58_PREFIX, TOKEN_DECIMALS, TOKEN_SYMBOL,
+	WASM_BINARY,
 };
-use sc_service::ChainType;
+use sc_service::{ChainType, Properties};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use std::path::Path;
+
+mod preset;
+
+use preset::PresetGenesis;
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Balance of the accounts pre-funded by the development and local testnet chains.
+const ENDOWMENT: Balance = 1 << 60;
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -31,9 +41,52 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Generate a validator account together with its Aura and GRANDPA keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
+	(
+		get_account_id_from_seed::<sr25519::Public>(s),
+		get_from_seed::<AuraId>(s),
+		get_from_seed::<GrandpaId>(s),
+	)
+}
+
+/// Chain properties telling wallets how to display the native token and addresses.
+pub fn chain_properties() -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+	properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+	properties.insert("ss58Format".into(), SS58_PREFIX.into());
+	properties
+}
+
+/// Rebuilds `spec` with `overrides` replacing the properties of the same name.
+pub fn override_properties(
+	spec: Box<dyn sc_service::ChainSpec>,
+	overrides: Properties,
+) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+	if overrides.is_empty() {
+		return Ok(spec)
+	}
+
+	// Chain specs can't be changed in place, so patch their JSON form and load it again.
+	let mut json: serde_json::Value =
+		serde_json::from_str(&spec.as_json(false)?).map_err(|e| e.to_string())?;
+	let properties = json
+		.as_object_mut()
+		.ok_or("chain spec is not a JSON object")?
+		.entry("properties")
+		.or_insert(serde_json::Value::Null);
+	if !properties.is_object() {
+		*properties = Properties::new().into();
+	}
+	properties.as_object_mut().expect("made an object above; qed").extend(overrides);
+
+	let json = serde_json::to_vec(&json).map_err(|e| e.to_string())?;
+	Ok(Box::new(ChainSpec::from_json_bytes(json)?))
+}
+
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { aura, grandpa }
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
@@ -48,18 +101,24 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		move || {
 			testnet_genesis(
 				wasm_binary,
-				// Initial PoA authorities
-				vec![authority_keys_from_seed("Alice")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
-				// Pre-funded accounts
-				vec![
-					get_account_id_from_seed::<sr25519::Public>("Alice"),
-					get_account_id_from_seed::<sr25519::Public>("Bob"),
-					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-				],
-				true,
+				PresetGenesis {
+					// Initial PoA authorities
+					authorities: vec![authority_keys_from_seed("Alice")],
+					council: vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
+					// Pre-funded accounts
+					balances: vec![
+						get_account_id_from_seed::<sr25519::Public>("Alice"),
+						get_account_id_from_seed::<sr25519::Public>("Bob"),
+						get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+						get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+					]
+					.into_iter()
+					.map(|k| (k, ENDOWMENT))
+					.collect(),
+					template_values: vec![],
+					treasury: ENDOWMENT,
+					vesting: vec![],
+				},
 			)
 		},
 		// Bootnodes
@@ -70,7 +129,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		None,
 		None,
 		// Properties
-		None,
+		Some(chain_properties()),
 		// Extensions
 		None,
 	))
@@ -88,26 +147,38 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		move || {
 			testnet_genesis(
 				wasm_binary,
-				// Initial PoA authorities
-				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
-				// Pre-funded accounts
-				vec![
-					get_account_id_from_seed::<sr25519::Public>("Alice"),
-					get_account_id_from_seed::<sr25519::Public>("Bob"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie"),
-					get_account_id_from_seed::<sr25519::Public>("Dave"),
-					get_account_id_from_seed::<sr25519::Public>("Eve"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-				],
-				true,
+				PresetGenesis {
+					// Initial PoA authorities
+					authorities: vec![
+						authority_keys_from_seed("Alice"),
+						authority_keys_from_seed("Bob"),
+					],
+					council: vec![
+						get_account_id_from_seed::<sr25519::Public>("Alice"),
+						get_account_id_from_seed::<sr25519::Public>("Bob"),
+					],
+					// Pre-funded accounts
+					balances: vec![
+						get_account_id_from_seed::<sr25519::Public>("Alice"),
+						get_account_id_from_seed::<sr25519::Public>("Bob"),
+						get_account_id_from_seed::<sr25519::Public>("Charlie"),
+						get_account_id_from_seed::<sr25519::Public>("Dave"),
+						get_account_id_from_seed::<sr25519::Public>("Eve"),
+						get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+						get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+						get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+						get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+						get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+						get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+						get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+					]
+					.into_iter()
+					.map(|k| (k, ENDOWMENT))
+					.collect(),
+					template_values: vec![],
+					treasury: ENDOWMENT,
+					vesting: vec![],
+				},
 			)
 		},
 		// Bootnodes
@@ -116,41 +187,84 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		None,
 		// Protocol ID
 		None,
+		None,
 		// Properties
+		Some(chain_properties()),
+		// Extensions
 		None,
+	))
+}
+
+/// Builds a chain spec from the preset at `path`.
+pub fn preset_config(path: &Path) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	let preset = preset::Preset::load(path)?;
+	let genesis = preset
+		.genesis()
+		.map_err(|e| format!("Invalid preset {}: {}", path.display(), e))?;
+	// Properties the preset leaves out keep their defaults.
+	let mut properties = chain_properties();
+	properties.extend(preset.properties.unwrap_or_default());
+
+	Ok(ChainSpec::from_genesis(
+		&preset.name,
+		&preset.id,
+		preset.chain_type,
+		move || testnet_genesis(wasm_binary, genesis.clone()),
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		preset.protocol_id.as_deref(),
 		None,
+		// Properties
+		Some(properties),
 		// Extensions
 		None,
 	))
 }
 
 /// Configure initial storage state for FRAME modules.
-fn testnet_genesis(
-	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool,
-) -> GenesisConfig {
+fn testnet_genesis(wasm_binary: &[u8], genesis: PresetGenesis) -> GenesisConfig {
+	let PresetGenesis {
+		authorities: initial_authorities,
+		council: council_members,
+		mut balances,
+		template_values,
+		treasury: treasury_endowment,
+		vesting,
+	} = genesis;
+	if treasury_endowment > 0 {
+		balances.push((Treasury::account_id(), treasury_endowment));
+	}
+
 	GenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
 			code: wasm_binary.to_vec(),
 		},
-		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
-		},
-		aura: AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
-		},
-		grandpa: GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+		balances: BalancesConfig { balances },
+		validator_set: ValidatorSetConfig {
+			initial_validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
 		},
-		sudo: SudoConfig {
-			// Assign network admin rights.
-			key: Some(root_key),
+		session: SessionConfig {
+			keys: initial_authorities
+				.iter()
+				.map(|x| (x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone())))
+				.collect(),
 		},
+		// Aura and GRANDPA authorities are set by the session pallet from the keys above.
+		aura: AuraConfig { authorities: vec![] },
+		grandpa: GrandpaConfig { authorities: vec![] },
+		// Network admin rights lie with the council, which reaches root through referenda.
+		council: CouncilConfig { members: council_members, phantom: Default::default() },
+		democracy: Default::default(),
 		transaction_payment: Default::default(),
+		template_module: TemplateModuleConfig { initial_values: template_values },
+		treasury: Default::default(),
+		// Each entry locks all of the account's balance but `liquid`, and unlocks it linearly over
+		// `length` blocks from `begin`.
+		vesting: VestingConfig { vesting },
 	}
 }
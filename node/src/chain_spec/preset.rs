@@ -0,0 +1,416 @@
+//! Human-written genesis presets, loaded with `--chain-preset <file>`.
+//!
+//! A preset is a TOML or JSON file (picked by extension) such as:
+//!
+//! ```toml
+//! name = "Consortium Testnet"
+//! id = "consortium_testnet"
+//! chain_type = "Live"
+//! council = ["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", "//Bob"]
+//!
+//! # Either a secret URI deriving all keys, or the keys themselves.
+//! authorities = [
+//!     "//Alice",
+//!     { account = "5FHneW46...", aura = "5FHneW46...", grandpa = "5GoNkf6W..." },
+//! ]
+//!
+//! # Funds of the treasury, on top of the balances below.
+//! treasury = 1_000_000_000_000_000
+//!
+//! [balances]
+//! "//Alice" = 1_000_000_000_000
+//! "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty" = "1000000000000000000000"
+//!
+//! # Values stored in pallet_template. Each account needs enough balance for the storage deposit.
+//! [template]
+//! "//Alice" = 42
+//!
+//! # Parts of the balances above that unlock linearly over `length` blocks from block `begin`.
+//! [vesting]
+//! "//Alice" = { locked = 500_000_000_000, begin = 0, length = 100_800 }
+//!
+//! # Merged over the node's default properties.
+//! [properties]
+//! tokenSymbol = "UNIT"
+//! tokenDecimals = 12
+//! ss58Format = 42
+//! ```
+//!
+//! Accounts and keys are given as SS58 addresses or secret URIs. Amounts are integers, or strings
+//! for amounts beyond what TOML integers can hold.
+
+use node_template_runtime::{AccountId, Balance, BlockNumber, TemplateStorageDeposit, Treasury};
+use sc_service::{ChainType, Properties};
+use serde::Deserialize;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::{crypto::Ss58Codec, ed25519, sr25519, Pair};
+use sp_finality_grandpa::AuthorityId as GrandpaId;
+use sp_runtime::traits::IdentifyAccount;
+use std::{collections::BTreeMap, path::Path};
+
+use super::AccountPublic;
+
+/// A genesis preset as written by a human.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+	pub name: String,
+	pub id: String,
+	#[serde(default)]
+	pub chain_type: ChainType,
+	pub protocol_id: Option<String>,
+	pub authorities: Vec<Authority>,
+	pub council: Vec<String>,
+	pub treasury: Option<Amount>,
+	#[serde(default)]
+	pub balances: BTreeMap<String, Amount>,
+	#[serde(default)]
+	pub template: BTreeMap<String, u32>,
+	#[serde(default)]
+	pub vesting: BTreeMap<String, Vesting>,
+	pub properties: Option<Properties>,
+}
+
+/// A validator, either derived from a single secret URI or given key by key.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Authority {
+	Seed(String),
+	#[serde(deny_unknown_fields)]
+	Keys {
+		account: String,
+		aura: String,
+		grandpa: String,
+	},
+}
+
+/// A balance, as an integer or a string of digits.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Amount {
+	Integer(u64),
+	Text(String),
+}
+
+/// A part of an account's balance that unlocks linearly over `length` blocks from `begin`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vesting {
+	pub locked: Amount,
+	#[serde(default)]
+	pub begin: BlockNumber,
+	pub length: BlockNumber,
+}
+
+/// The parts of the genesis state that differ between chains. Presets are checked and decoded
+/// into one, the built-in chains fill it in directly.
+#[derive(Clone)]
+pub struct PresetGenesis {
+	pub authorities: Vec<(AccountId, AuraId, GrandpaId)>,
+	pub council: Vec<AccountId>,
+	pub balances: Vec<(AccountId, Balance)>,
+	pub template_values: Vec<(AccountId, u32)>,
+	pub treasury: Balance,
+	/// Vesting schedules as `(account, begin, length, liquid)`, like `pallet_vesting` takes them.
+	pub vesting: Vec<(AccountId, BlockNumber, BlockNumber, Balance)>,
+}
+
+/// Decodes `value` as an SS58 address, or derives the public key of a secret URI.
+fn public<P: Pair>(field: &str, value: &str) -> Result<P::Public, String>
+where
+	P::Public: Ss58Codec,
+{
+	if let Ok(public) = P::Public::from_ss58check(value) {
+		return Ok(public)
+	}
+	P::from_string(value, None)
+		.map(|pair| pair.public())
+		.map_err(|_| format!("{}: `{}` is neither an SS58 address nor a secret URI", field, value))
+}
+
+fn account(field: &str, value: &str) -> Result<AccountId, String> {
+	public::<sr25519::Pair>(field, value).map(|key| AccountPublic::from(key).into_account())
+}
+
+fn amount(field: &str, value: &Amount) -> Result<Balance, String> {
+	match value {
+		Amount::Integer(amount) => Ok((*amount).into()),
+		Amount::Text(amount) => amount
+			.replace('_', "")
+			.parse()
+			.map_err(|_| format!("{}: `{}` is not an amount", field, amount)),
+	}
+}
+
+impl Preset {
+	/// Reads a preset from a `.toml` or `.json` file.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| format!("Error reading preset {}: {}", path.display(), e))?;
+		let preset = match path.extension().and_then(|extension| extension.to_str()) {
+			Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+			Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+			_ => Err("presets must be `.toml` or `.json` files".into()),
+		};
+		preset.map_err(|e| format!("Invalid preset {}: {}", path.display(), e))
+	}
+
+	/// Checks the preset and decodes the accounts and keys it refers to.
+	pub fn genesis(&self) -> Result<PresetGenesis, String> {
+		if self.authorities.is_empty() {
+			return Err("authorities: at least one authority is required".into())
+		}
+		let mut authorities: Vec<(AccountId, AuraId, GrandpaId)> = Vec::new();
+		for (i, authority) in self.authorities.iter().enumerate() {
+			let field = format!("authorities[{}]", i);
+			let keys = match authority {
+				Authority::Seed(seed) => (
+					account(&field, seed)?,
+					public::<sr25519::Pair>(&field, seed)?.into(),
+					public::<ed25519::Pair>(&field, seed)?.into(),
+				),
+				Authority::Keys { account: who, aura, grandpa } => (
+					account(&format!("{}.account", field), who)?,
+					public::<sr25519::Pair>(&format!("{}.aura", field), aura)?.into(),
+					public::<ed25519::Pair>(&format!("{}.grandpa", field), grandpa)?.into(),
+				),
+			};
+			if authorities.iter().any(|known| known.0 == keys.0) {
+				return Err(format!("{}: account {} is listed twice", field, keys.0))
+			}
+			authorities.push(keys);
+		}
+
+		let mut balances: Vec<(AccountId, Balance)> = Vec::new();
+		for (who, amount) in &self.balances {
+			let field = format!("balances.\"{}\"", who);
+			let account = account(&field, who)?;
+			let amount = amount(&field, amount)?;
+			if balances.iter().any(|known| known.0 == account) {
+				return Err(format!("{}: account {} is listed twice", field, account))
+			}
+			// The treasury is funded by the `treasury` field, next to these balances.
+			if account == Treasury::account_id() {
+				return Err(format!(
+					"{}: fund the treasury with the `treasury` field instead",
+					field
+				))
+			}
+			balances.push((account, amount));
+		}
+
+		if self.council.is_empty() {
+			return Err("council: at least one member is required".into())
+		}
+		let mut council: Vec<AccountId> = Vec::new();
+		for (i, member) in self.council.iter().enumerate() {
+			let field = format!("council[{}]", i);
+			let member = account(&field, member)?;
+			if council.contains(&member) {
+				return Err(format!("{}: account {} is listed twice", field, member))
+			}
+			council.push(member);
+		}
+
+		let mut template_values: Vec<(AccountId, u32)> = Vec::new();
+		for (who, value) in &self.template {
+			let field = format!("template.\"{}\"", who);
+			let account = account(&field, who)?;
+			if template_values.iter().any(|known| known.0 == account) {
+				return Err(format!("{}: account {} is listed twice", field, account))
+			}
+			// Genesis reserves the storage deposit, which would otherwise abort the chain start.
+			let deposit = TemplateStorageDeposit::get();
+			if !balances.iter().any(|(known, balance)| *known == account && *balance >= deposit) {
+				return Err(format!(
+					"{}: account {} needs a balance of at least {} for the storage deposit",
+					field, account, deposit
+				))
+			}
+			template_values.push((account, *value));
+		}
+
+		let mut vesting: Vec<(AccountId, BlockNumber, BlockNumber, Balance)> = Vec::new();
+		for (who, schedule) in &self.vesting {
+			let field = format!("vesting.\"{}\"", who);
+			let account = account(&field, who)?;
+			if vesting.iter().any(|known| known.0 == account) {
+				return Err(format!("{}: account {} is listed twice", field, account))
+			}
+			// Vesting locks the free balance, which the template storage deposit is not part of.
+			let mut balance = balances
+				.iter()
+				.find(|(known, _)| *known == account)
+				.map_or(0, |(_, balance)| *balance);
+			if template_values.iter().any(|(known, _)| *known == account) {
+				balance -= TemplateStorageDeposit::get();
+			}
+			let locked = amount(&format!("{}.locked", field), &schedule.locked)?;
+			if locked == 0 || locked > balance {
+				return Err(format!(
+					"{}.locked: expected more than 0 and at most the free balance of {}, {}",
+					field, account, balance
+				))
+			}
+			// Genesis rejects schedules that unlock less than one unit per block.
+			if schedule.length == 0 || locked < schedule.length.into() {
+				return Err(format!("{}.length: expected between 1 and the locked amount", field))
+			}
+			vesting.push((account, schedule.begin, schedule.length, balance - locked));
+		}
+
+		let treasury = match &self.treasury {
+			Some(treasury) => amount("treasury", treasury)?,
+			None => 0,
+		};
+
+		if let Some(properties) = &self.properties {
+			check_properties(properties)?;
+		}
+
+		Ok(PresetGenesis { authorities, council, balances, template_values, treasury, vesting })
+	}
+}
+
+/// Checks the properties wallets rely on, leaving any others alone.
+fn check_properties(properties: &Properties) -> Result<(), String> {
+	if let Some(symbol) = properties.get("tokenSymbol") {
+		if !symbol.is_string() {
+			return Err("properties.tokenSymbol: expected a string".into())
+		}
+	}
+	if let Some(decimals) = properties.get("tokenDecimals") {
+		if !decimals.as_u64().map_or(false, |decimals| decimals <= u8::MAX as u64) {
+			return Err("properties.tokenDecimals: expected an integer between 0 and 255".into())
+		}
+	}
+	if let Some(format) = properties.get("ss58Format") {
+		// Larger prefixes do not fit the two byte SS58 encoding.
+		if !format.as_u64().map_or(false, |format| format < 16_384) {
+			return Err("properties.ss58Format: expected an integer between 0 and 16383".into())
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chain_spec::{authority_keys_from_seed, get_account_id_from_seed};
+
+	fn preset(toml: &str) -> Preset {
+		toml::from_str(toml).unwrap()
+	}
+
+	#[test]
+	fn decodes_seeds_and_addresses() {
+		let genesis = preset(
+			r#"
+			name = "Test"
+			id = "test"
+			council = ["5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"]
+			treasury = "2_000"
+			authorities = [
+				"//Alice",
+				{ account = "//Bob", aura = "//Bob", grandpa = "//Bob" },
+			]
+
+			[balances]
+			"//Alice" = 1_000
+			"//Charlie" = "1_000_000_000_000_000_000_000"
+
+			[template]
+			"//Charlie" = 42
+
+			[vesting]
+			"//Alice" = { locked = 600, length = 100 }
+			"#,
+		)
+		.genesis()
+		.unwrap();
+
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		assert_eq!(
+			genesis.authorities,
+			vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")]
+		);
+		assert_eq!(genesis.council, vec![bob]);
+		assert_eq!(
+			genesis.balances,
+			vec![(alice.clone(), 1_000), (charlie.clone(), 1_000_000_000_000_000_000_000)]
+		);
+		assert_eq!(genesis.template_values, vec![(charlie, 42)]);
+		assert_eq!(genesis.treasury, 2_000);
+		assert_eq!(genesis.vesting, vec![(alice, 0, 100, 400)]);
+	}
+
+	#[test]
+	fn errors_point_at_the_field() {
+		let error = |toml: &str| preset(toml).genesis().unwrap_err();
+		let base = "name = \"Test\"\nid = \"test\"\ncouncil = [\"//Alice\"]\n";
+
+		assert_eq!(
+			error(&format!("{}authorities = []", base)),
+			"authorities: at least one authority is required"
+		);
+		assert!(error(&format!(
+			"{}authorities = [\"//Alice\", {{ account = \"//Bob\", aura = \"nope\", grandpa = \"//Bob\" }}]",
+			base
+		))
+		.starts_with("authorities[1].aura: `nope`"));
+		assert_eq!(
+			error(&format!(
+				"{}authorities = [\"//Alice\"]\n[balances]\n\"//Bob\" = \"lots\"",
+				base
+			)),
+			"balances.\"//Bob\": `lots` is not an amount"
+		);
+		assert_eq!(
+			error(&format!(
+				"{}authorities = [\"//Alice\"]\n[properties]\ntokenDecimals = -1",
+				base
+			)),
+			"properties.tokenDecimals: expected an integer between 0 and 255"
+		);
+		assert!(error(&format!(
+			"{}authorities = [\"//Alice\"]\n[balances]\n\"//Bob\" = 999\n[template]\n\"//Bob\" = 1",
+			base
+		))
+		.ends_with(&format!(
+			"needs a balance of at least {} for the storage deposit",
+			TemplateStorageDeposit::get()
+		)));
+		assert!(error(&format!(
+			"{}authorities = [\"//Alice\"]\n[balances]\n\"{}\" = 1_000",
+			base,
+			Treasury::account_id().to_ss58check()
+		))
+		.ends_with("fund the treasury with the `treasury` field instead"));
+		assert_eq!(
+			error(&format!(
+				"{}authorities = [\"//Alice\"]\n[balances]\n\"//Bob\" = 1_000\n[vesting]\n\"//Bob\" = {{ locked = 10, length = 100 }}",
+				base
+			)),
+			"vesting.\"//Bob\".length: expected between 1 and the locked amount"
+		);
+	}
+
+	#[test]
+	fn example_preset_is_valid() {
+		let genesis = preset(include_str!("../../presets/example.toml")).genesis().unwrap();
+		assert_eq!(
+			genesis.authorities,
+			vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")]
+		);
+	}
+
+	#[test]
+	fn rejects_unknown_fields() {
+		assert!(toml::from_str::<Preset>(
+			"name = \"Test\"\nid = \"test\"\ncouncil = []\nauthorities = []\nsudo = \"//Bob\""
+		)
+		.is_err());
+	}
+}
@@ -0,0 +1,349 @@
+//! `benchmark check-weights`: compares the committed weight files with freshly measured ones.
+//!
+//! Both are read as the Rust source the benchmark CLI writes, so any file in that layout can be
+//! checked, whether it was generated or edited by hand.
+
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+/// One term of a weight function.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Term {
+	/// The weight paid whatever the components are.
+	Base,
+	/// The weight paid per unit of a component.
+	PerComponent(String),
+	/// The number of storage reads, per unit of a component if given.
+	Reads(Option<String>),
+	/// The number of storage writes, per unit of a component if given.
+	Writes(Option<String>),
+}
+
+impl Term {
+	/// Storage accesses are counted rather than measured, so any change is a drift.
+	fn is_counted(&self) -> bool {
+		matches!(self, Self::Reads(_) | Self::Writes(_))
+	}
+}
+
+impl fmt::Display for Term {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let per = |f: &mut fmt::Formatter, what, component: &Option<String>| match component {
+			Some(component) => write!(f, "{} per {}", what, component),
+			None => write!(f, "{}", what),
+		};
+		match self {
+			Self::Base => write!(f, "base"),
+			Self::PerComponent(component) => write!(f, "per {}", component),
+			Self::Reads(component) => per(f, "reads", component),
+			Self::Writes(component) => per(f, "writes", component),
+		}
+	}
+}
+
+/// The terms of every weight function in a file, by function name.
+pub type Weights = BTreeMap<String, BTreeMap<Term, u64>>;
+
+/// Reads the weight functions out of a weight file.
+pub fn parse(source: &str) -> Weights {
+	let mut weights = Weights::new();
+	let mut current = None;
+	for line in source.lines().map(str::trim) {
+		if line.starts_with("//") {
+			continue
+		}
+		if let Some(signature) = line.strip_prefix("fn ") {
+			current = signature.split('(').next().map(|name| name.to_string());
+			continue
+		}
+		if line == "}" {
+			current = None;
+			continue
+		}
+		let (extrinsic, value) = match (&current, literal(line)) {
+			(Some(extrinsic), Some(value)) => (extrinsic, value),
+			_ => continue,
+		};
+		let component = line
+			.split(".saturating_mul(")
+			.nth(1)
+			.and_then(|rest| rest.split(" as Weight").next())
+			.map(|component| component.to_string());
+		let term = if line.contains(".reads(") {
+			Term::Reads(component)
+		} else if line.contains(".writes(") {
+			Term::Writes(component)
+		} else {
+			component.map_or(Term::Base, Term::PerComponent)
+		};
+		weights.entry(extrinsic.clone()).or_default().insert(term, value);
+	}
+	weights
+}
+
+/// The first `<number> as Weight` on a line.
+fn literal(line: &str) -> Option<u64> {
+	let (head, _) = line.split_once(" as Weight")?;
+	let digits = head.rsplit(|c: char| !(c.is_ascii_digit() || c == '_')).next()?;
+	digits.replace('_', "").parse().ok()
+}
+
+/// A weight that differs between the committed and the measured file by more than allowed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+	pub pallet: String,
+	pub extrinsic: String,
+	pub term: Term,
+	/// `None` if the extrinsic has no committed weight.
+	pub committed: Option<u64>,
+	/// `None` if the extrinsic was not measured.
+	pub measured: Option<u64>,
+}
+
+impl Drift {
+	/// How far the measured weight is from the committed one, in percent.
+	fn percent(&self) -> Option<f64> {
+		let (committed, measured) = (self.committed?, self.measured?);
+		Some(match committed {
+			0 if measured == 0 => 0.0,
+			0 => f64::INFINITY,
+			_ => (measured as f64 - committed as f64) / committed as f64 * 100.0,
+		})
+	}
+}
+
+/// Every term of `pallet` that drifted by more than `threshold` percent, or whose storage
+/// accesses changed. Terms missing from a function count as zero.
+pub fn compare(
+	pallet: &str,
+	committed: &Weights,
+	measured: &Weights,
+	threshold: f64,
+) -> Vec<Drift> {
+	let drift = |extrinsic: &str, term: &Term, committed, measured| Drift {
+		pallet: pallet.into(),
+		extrinsic: extrinsic.into(),
+		term: term.clone(),
+		committed,
+		measured,
+	};
+	let mut extrinsics: Vec<_> = committed.keys().chain(measured.keys()).collect();
+	extrinsics.sort();
+	extrinsics.dedup();
+
+	let mut drifts = Vec::new();
+	for extrinsic in extrinsics {
+		let (old, new) = match (committed.get(extrinsic), measured.get(extrinsic)) {
+			(Some(old), Some(new)) => (old, new),
+			(old, new) => {
+				drifts.push(drift(
+					extrinsic,
+					&Term::Base,
+					old.map(|terms| terms.get(&Term::Base).copied().unwrap_or_default()),
+					new.map(|terms| terms.get(&Term::Base).copied().unwrap_or_default()),
+				));
+				continue
+			},
+		};
+		let mut terms: Vec<_> = old.keys().chain(new.keys()).collect();
+		terms.sort();
+		terms.dedup();
+		for term in terms {
+			let value = |terms: &BTreeMap<Term, u64>| Some(terms.get(term).copied().unwrap_or(0));
+			let found = drift(extrinsic, term, value(old), value(new));
+			let drifted = if term.is_counted() {
+				found.committed != found.measured
+			} else {
+				found.percent().map_or(true, |percent| percent.abs() > threshold)
+			};
+			if drifted {
+				drifts.push(found);
+			}
+		}
+	}
+	drifts
+}
+
+/// Compares every measured file in `measured` with the committed file of the same name in
+/// `committed`. A pallet without a committed file has no committed weights, so every one of its
+/// extrinsics is reported.
+pub fn check(committed: &Path, measured: &Path, threshold: f64) -> io::Result<Vec<Drift>> {
+	let mut files: Vec<_> = fs::read_dir(measured)?
+		.map(|entry| entry.map(|entry| entry.path()))
+		.collect::<io::Result<_>>()?;
+	files.sort();
+
+	let mut drifts = Vec::new();
+	for file in files {
+		let (name, pallet) = match (file.file_name(), file.file_stem()) {
+			(Some(name), Some(pallet)) => (name, pallet.to_string_lossy()),
+			_ => continue,
+		};
+		let committed_file = committed.join(name);
+		let committed_weights = if committed_file.is_file() {
+			parse(&fs::read_to_string(committed_file)?)
+		} else {
+			Weights::new()
+		};
+		drifts.extend(compare(
+			&pallet,
+			&committed_weights,
+			&parse(&fs::read_to_string(&file)?),
+			threshold,
+		));
+	}
+	Ok(drifts)
+}
+
+/// Prints the drifted weights as a table, and fails if there are any.
+pub fn report(drifts: &[Drift], threshold: f64) -> sc_cli::Result<()> {
+	if drifts.is_empty() {
+		println!("All weights are within {}% of the committed ones.", threshold);
+		return Ok(())
+	}
+
+	let value = |value: Option<u64>| value.map_or("-".into(), |value| value.to_string());
+	println!(
+		"{:<20} {:<32} {:<16} {:>16} {:>16} {:>10}",
+		"pallet", "extrinsic", "term", "committed", "measured", "drift"
+	);
+	for drift in drifts {
+		println!(
+			"{:<20} {:<32} {:<16} {:>16} {:>16} {:>10}",
+			drift.pallet,
+			drift.extrinsic,
+			drift.term.to_string(),
+			value(drift.committed),
+			value(drift.measured),
+			drift.percent().map_or("-".into(), |percent| format!("{:+.1}%", percent)),
+		);
+	}
+	println!(
+		"A committed weight of `-` means the extrinsic, or its whole pallet, has no weight file."
+	);
+	Err(sc_cli::Error::Input(format!(
+		"{} weights drifted by more than {}% from the committed ones",
+		drifts.len(),
+		threshold
+	)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COMMITTED: &str = "
+		impl<T: frame_system::Config> pallet_template::WeightInfo for WeightInfo<T> {
+			// Storage: TemplateModule Something (r:1 w:1)
+			fn do_something() -> Weight {
+				(25_000_000 as Weight)
+					.saturating_add(T::DbWeight::get().reads(2 as Weight))
+					.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			}
+			fn remark(b: u32, ) -> Weight {
+				(1_000_000 as Weight)
+					// Standard Error: 0
+					.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
+			}
+		}
+	";
+
+	fn measured(base: u64, per_b: u64, writes: u64) -> Weights {
+		parse(
+			&COMMITTED
+				.replace("25_000_000", &base.to_string())
+				.replace("(1_000 as Weight)", &format!("({} as Weight)", per_b))
+				.replace("writes(2", &format!("writes({}", writes)),
+		)
+	}
+
+	#[test]
+	fn parses_every_term() {
+		let weights = parse(COMMITTED);
+		assert_eq!(
+			weights["do_something"],
+			BTreeMap::from([
+				(Term::Base, 25_000_000),
+				(Term::Reads(None), 2),
+				(Term::Writes(None), 2)
+			])
+		);
+		assert_eq!(
+			weights["remark"],
+			BTreeMap::from([(Term::Base, 1_000_000), (Term::PerComponent("b".into()), 1_000)])
+		);
+	}
+
+	#[test]
+	fn drift_within_the_threshold_passes() {
+		let committed = parse(COMMITTED);
+		assert_eq!(compare("template", &committed, &measured(27_000_000, 1_050, 2), 10.0), vec![]);
+	}
+
+	#[test]
+	fn drift_beyond_the_threshold_is_reported() {
+		let committed = parse(COMMITTED);
+		let drifts = compare("template", &committed, &measured(30_000_000, 1_200, 3), 10.0);
+		let found: Vec<_> = drifts
+			.iter()
+			.map(|drift| (drift.extrinsic.as_str(), drift.term.to_string(), drift.percent()))
+			.collect();
+		assert_eq!(
+			found,
+			vec![
+				("do_something", "base".into(), Some(20.0)),
+				("do_something", "writes".into(), Some(50.0)),
+				("remark", "per b".into(), Some(20.0)),
+			]
+		);
+	}
+
+	#[test]
+	fn missing_extrinsics_are_reported() {
+		let committed = parse(COMMITTED);
+		let mut measured = parse(COMMITTED);
+		measured.remove("remark");
+		assert_eq!(
+			compare("template", &committed, &measured, 10.0),
+			vec![Drift {
+				pallet: "template".into(),
+				extrinsic: "remark".into(),
+				term: Term::Base,
+				committed: Some(1_000_000),
+				measured: None,
+			}]
+		);
+	}
+
+	#[test]
+	fn pallets_without_a_committed_file_are_reported() {
+		let dir = std::env::temp_dir().join(format!(
+			"node-template-check-weights-test-{}-{}",
+			std::process::id(),
+			std::time::SystemTime::now()
+				.duration_since(std::time::UNIX_EPOCH)
+				.unwrap()
+				.as_nanos()
+		));
+		let (committed, measured) = (dir.join("committed"), dir.join("measured"));
+		fs::create_dir_all(&committed).unwrap();
+		fs::create_dir_all(&measured).unwrap();
+		fs::write(committed.join("pallet_template.rs"), COMMITTED).unwrap();
+		fs::write(measured.join("pallet_template.rs"), COMMITTED).unwrap();
+		fs::write(measured.join("pallet_balances.rs"), COMMITTED).unwrap();
+
+		let drifts = check(&committed, &measured, 10.0);
+		let _ = fs::remove_dir_all(&dir);
+		let found: Vec<_> = drifts
+			.unwrap()
+			.into_iter()
+			.map(|drift| (drift.pallet, drift.extrinsic, drift.committed, drift.measured))
+			.collect();
+		assert_eq!(
+			found,
+			vec![
+				("pallet_balances".into(), "do_something".into(), None, Some(25_000_000)),
+				("pallet_balances".into(), "remark".into(), None, Some(1_000_000)),
+			]
+		);
+	}
+}
@@ -1,4 +1,9 @@
-use sc_cli::RunCmd;
+use sc_cli::{CliConfiguration, NodeKeyParams, RunCmd, SharedParams};
+use sc_service::Properties;
+use std::{
+	path::{Path, PathBuf},
+	str::FromStr,
+};
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +12,48 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks on demand instead of running Aura and GRANDPA.
+	///
+	/// `instant` seals a block for every transaction that enters the pool, `manual` only seals
+	/// when asked to through the `engine_createBlock` RPC and a number seals a block every that
+	/// many milliseconds. Blocks can be finalized with `engine_finalizeBlock` in every mode.
+	#[clap(long)]
+	pub sealing: Option<Sealing>,
+
+	/// Build the chain spec from a TOML or JSON genesis preset instead of `--chain`.
+	#[clap(long, global = true, parse(from_os_str))]
+	pub chain_preset: Option<PathBuf>,
+}
+
+/// How blocks are sealed when running without Aura and GRANDPA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction is imported into the pool.
+	Instant,
+	/// Seal blocks only when requested over RPC.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"instant" => Self::Instant,
+			"manual" => Self::Manual,
+			millis => match millis.parse() {
+				Ok(millis) if millis > 0 => Self::Interval(millis),
+				_ =>
+					return Err(format!(
+						"expected `instant`, `manual` or a number of milliseconds, got `{}`",
+						millis
+					)),
+			},
+		})
+	}
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -16,7 +63,7 @@ pub enum Subcommand {
 	Key(sc_cli::KeySubcommand),
 
 	/// Build a chain specification.
-	BuildSpec(sc_cli::BuildSpecCmd),
+	BuildSpec(BuildSpecCmd),
 
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
@@ -38,7 +85,7 @@ pub enum Subcommand {
 
 	/// The custom benchmark subcommand benchmarking runtime pallets.
 	#[clap(name = "benchmark", about = "Benchmark runtime pallets.")]
-	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+	Benchmark(BenchmarkCmd),
 
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
@@ -48,3 +95,112 @@ pub enum Subcommand {
 	#[cfg(not(feature = "try-runtime"))]
 	TryRuntime,
 }
+
+/// `build-spec` with overrides for the chain properties wallets read.
+#[derive(Debug, clap::Parser)]
+pub struct BuildSpecCmd {
+	#[clap(flatten)]
+	pub inner: sc_cli::BuildSpecCmd,
+
+	/// Override the symbol of the native token.
+	#[clap(long)]
+	pub token_symbol: Option<String>,
+
+	/// Override the number of decimals of the native token.
+	#[clap(long)]
+	pub token_decimals: Option<u8>,
+
+	/// Override the SS58 address format.
+	#[clap(long)]
+	pub ss58_format: Option<u16>,
+}
+
+impl BuildSpecCmd {
+	/// The chain properties given on the command line.
+	pub fn property_overrides(&self) -> Properties {
+		let mut properties = Properties::new();
+		if let Some(symbol) = &self.token_symbol {
+			properties.insert("tokenSymbol".into(), symbol.clone().into());
+		}
+		if let Some(decimals) = self.token_decimals {
+			properties.insert("tokenDecimals".into(), decimals.into());
+		}
+		if let Some(format) = self.ss58_format {
+			properties.insert("ss58Format".into(), format.into());
+		}
+		properties
+	}
+}
+
+impl CliConfiguration for BuildSpecCmd {
+	fn shared_params(&self) -> &SharedParams {
+		self.inner.shared_params()
+	}
+
+	fn node_key_params(&self) -> Option<&NodeKeyParams> {
+		self.inner.node_key_params()
+	}
+}
+
+/// `benchmark`, which can also check the committed weights against fresh measurements.
+#[derive(Debug, clap::Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct BenchmarkCmd {
+	#[clap(subcommand)]
+	pub subcommand: Option<BenchmarkSubcommand>,
+
+	#[clap(flatten)]
+	pub inner: frame_benchmarking_cli::BenchmarkCmd,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum BenchmarkSubcommand {
+	/// Rerun the benchmarks and compare them to the committed weight files.
+	///
+	/// Exits with an error, after printing every drifted weight, when a base weight or a
+	/// per-component weight moved by more than `--threshold` percent, or when the number of
+	/// storage reads and writes changed.
+	CheckWeights(CheckWeightsCmd),
+}
+
+/// Options of `benchmark check-weights`.
+#[derive(Debug, clap::Parser)]
+pub struct CheckWeightsCmd {
+	/// The chain to benchmark against.
+	#[clap(long, default_value = "dev")]
+	pub chain: String,
+
+	/// Number of steps across component ranges.
+	#[clap(long, default_value = "50")]
+	pub steps: u32,
+
+	/// Number of times every step is repeated.
+	#[clap(long, default_value = "20")]
+	pub repeat: u32,
+
+	/// The directory holding the committed weight files, one `<pallet>.rs` per pallet.
+	#[clap(long, parse(from_os_str), default_value = "runtime/src/weights")]
+	pub weights: PathBuf,
+
+	/// The largest drift, in percent, tolerated for a base or per-component weight.
+	#[clap(long, default_value = "10")]
+	pub threshold: f64,
+}
+
+impl CheckWeightsCmd {
+	/// The `benchmark` invocation measuring every pallet in `define_benchmarks!`, writing one
+	/// weight file per pallet into `output`.
+	pub fn benchmark_cmd(&self, output: &Path) -> frame_benchmarking_cli::BenchmarkCmd {
+		<frame_benchmarking_cli::BenchmarkCmd as clap::Parser>::parse_from([
+			"benchmark".into(),
+			format!("--chain={}", self.chain),
+			"--execution=wasm".into(),
+			"--wasm-execution=compiled".into(),
+			"--pallet=*".into(),
+			"--extrinsic=*".into(),
+			format!("--steps={}", self.steps),
+			format!("--repeat={}", self.repeat),
+			format!("--output={}", output.display()),
+		])
+	}
+}
@@ -1,6 +1,6 @@
 use crate::{
-	chain_spec,
-	cli::{Cli, Subcommand},
+	chain_spec, check_weights,
+	cli::{BenchmarkSubcommand, Cli, Subcommand},
 	service,
 };
 use node_template_runtime::Block;
@@ -33,6 +33,10 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		if let Some(preset) = &self.chain_preset {
+			return Ok(Box::new(chain_spec::preset_config(preset)?))
+		}
+
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
@@ -54,7 +58,11 @@ pub fn run() -> sc_cli::Result<()> {
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
 		Some(Subcommand::BuildSpec(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
-			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+			runner.sync_run(|config| {
+				let chain_spec =
+					chain_spec::override_properties(config.chain_spec, cmd.property_overrides())?;
+				cmd.inner.run(chain_spec, config.network)
+			})
 		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -100,9 +108,31 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		Some(Subcommand::Benchmark(cmd)) =>
 			if cfg!(feature = "runtime-benchmarks") {
-				let runner = cli.create_runner(cmd)?;
+				match &cmd.subcommand {
+					None => {
+						let runner = cli.create_runner(&cmd.inner)?;
+
+						runner.sync_run(|config| {
+							cmd.inner.run::<Block, service::ExecutorDispatch>(config)
+						})
+					},
+					Some(BenchmarkSubcommand::CheckWeights(check)) => {
+						let measured = std::env::temp_dir()
+							.join(format!("node-template-check-weights-{}", std::process::id()));
+						std::fs::create_dir_all(&measured)?;
+						let benchmark = check.benchmark_cmd(&measured);
 
-				runner.sync_run(|config| cmd.run::<Block, service::ExecutorDispatch>(config))
+						// Collect the result first so the directory is removed on every path.
+						let drifts = cli.create_runner(&benchmark).and_then(|runner| {
+							runner.sync_run(|config| {
+								benchmark.run::<Block, service::ExecutorDispatch>(config)
+							})?;
+							Ok(check_weights::check(&check.weights, &measured, check.threshold)?)
+						});
+						let _ = std::fs::remove_dir_all(&measured);
+						check_weights::report(&drifts?, check.threshold)
+					},
+				}
 			} else {
 				Err("Benchmarking wasn't enabled when building the node. You can enable it with \
 				     `--features runtime-benchmarks`."
@@ -127,8 +157,13 @@ pub fn run() -> sc_cli::Result<()> {
 			.into()),
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let sealing = cli.sealing;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				match sealing {
+					Some(sealing) => service::new_manual_seal(config, sealing),
+					None => service::new_full(config),
+				}
+				.map_err(sc_cli::Error::Service)
 			})
 		},
 	}
@@ -0,0 +1,177 @@
+//! Serves a [`SyncCryptoStore`] to nodes using it as their remote keystore.
+//!
+//! Speaks the protocol of [`crate::remote_keystore`], answering each request line with a
+//! response line. Backs the `node-template-keystore` binary.
+
+use crate::remote_keystore::{
+	crypto_name, parse_crypto, Call, PublicKey, Request, Response, RpcError, LOG_TARGET,
+};
+use serde_json::Value;
+use sp_core::{
+	crypto::{CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::SyncCryptoStore;
+use std::{
+	io::{self, BufRead, BufReader, Write},
+	os::unix::net::{UnixListener, UnixStream},
+	sync::Arc,
+};
+
+/// JSON-RPC error code for requests that could not be parsed.
+const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code for requests the keystore refused or failed to execute.
+const KEYSTORE_ERROR: i64 = 1;
+
+impl Response {
+	fn new(id: Option<u64>, outcome: Result<Value, (i64, String)>) -> Self {
+		let (result, error) = match outcome {
+			Ok(result) => (Some(result), None),
+			Err((code, message)) => (None, Some(RpcError { code, message })),
+		};
+		Self { jsonrpc: "2.0".into(), id, result, error }
+	}
+}
+
+fn parse_key_type(name: &str) -> Result<KeyTypeId, String> {
+	KeyTypeId::try_from(name).map_err(|()| format!("invalid key type `{}`", name))
+}
+
+/// Executes a single call against `store`.
+fn execute(store: &dyn SyncCryptoStore, call: Call) -> Result<Value, String> {
+	let to_value = |value| serde_json::to_value(value).map_err(|e| e.to_string());
+	match call {
+		Call::Keys { key_type } => {
+			let keys = SyncCryptoStore::keys(store, parse_key_type(&key_type)?)
+				.map_err(|e| e.to_string())?
+				.into_iter()
+				.map(|key| PublicKey { crypto: crypto_name(key.0), public: key.1.into() })
+				.collect::<Vec<_>>();
+			to_value(keys)
+		},
+		Call::Generate { key_type, crypto, seed } => {
+			let id = parse_key_type(&key_type)?;
+			let seed = seed.as_deref();
+			let public = match parse_crypto(&crypto)? {
+				sr25519::CRYPTO_ID => store.sr25519_generate_new(id, seed).map(|p| p.0.to_vec()),
+				ed25519::CRYPTO_ID => store.ed25519_generate_new(id, seed).map(|p| p.0.to_vec()),
+				ecdsa::CRYPTO_ID => store.ecdsa_generate_new(id, seed).map(|p| p.0.to_vec()),
+				_ => return Err(format!("unsupported crypto type `{}`", crypto)),
+			}
+			.map_err(|e| e.to_string())?;
+			to_value(Bytes(public))
+		},
+		Call::Insert { key_type, suri, public } => {
+			store
+				.insert_unknown(parse_key_type(&key_type)?, &suri, &public)
+				.map_err(|()| "failed to insert key".to_string())?;
+			Ok(Value::Null)
+		},
+		Call::HasKeys { keys } => {
+			let keys = keys
+				.into_iter()
+				.map(|(public, key_type)| Ok((public.0, parse_key_type(&key_type)?)))
+				.collect::<Result<Vec<_>, String>>()?;
+			to_value(store.has_keys(&keys))
+		},
+		Call::Sign { key_type, crypto, public, message } => {
+			let key = CryptoTypePublicPair(parse_crypto(&crypto)?, public.0);
+			let signature = store
+				.sign_with(parse_key_type(&key_type)?, &key, &message)
+				.map_err(|e| e.to_string())?;
+			to_value(signature.map(Bytes))
+		},
+		Call::SignPrehashed { key_type, public, message } => {
+			let public = ecdsa::Public::try_from(&public[..])
+				.map_err(|()| "invalid ECDSA public key".to_string())?;
+			let message = <[u8; 32]>::try_from(&message[..])
+				.map_err(|_| "pre-hashed message must be 32 bytes".to_string())?;
+			let signature = store
+				.ecdsa_sign_prehashed(parse_key_type(&key_type)?, &public, &message)
+				.map_err(|e| e.to_string())?;
+			to_value(signature.map(|signature| Bytes(signature.0.to_vec())))
+		},
+	}
+}
+
+fn serve_connection(store: &dyn SyncCryptoStore, stream: UnixStream) -> io::Result<()> {
+	let mut writer = stream.try_clone()?;
+	for line in BufReader::new(stream).lines() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue
+		}
+		let response = match serde_json::from_str::<Request>(&line) {
+			Ok(request) => Response::new(
+				Some(request.id),
+				execute(store, request.call).map_err(|e| (KEYSTORE_ERROR, e)),
+			),
+			Err(e) => Response::new(None, Err((PARSE_ERROR, e.to_string()))),
+		};
+		let mut line = serde_json::to_vec(&response)?;
+		line.push(b'\n');
+		writer.write_all(&line)?;
+	}
+	Ok(())
+}
+
+/// Answers keystore requests from `store` on every connection accepted by `listener`.
+pub fn serve(store: Arc<dyn SyncCryptoStore>, listener: UnixListener) -> io::Result<()> {
+	for stream in listener.incoming() {
+		let stream = stream?;
+		let store = store.clone();
+		std::thread::spawn(move || {
+			if let Err(e) = serve_connection(&*store, stream) {
+				log::debug!(target: LOG_TARGET, "Connection closed: {}", e);
+			}
+		});
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::remote_keystore::RemoteKeystore;
+	use sc_keystore::LocalKeystore;
+	use sp_core::{crypto::key_types::AURA, Pair};
+	use std::path::PathBuf;
+
+	fn start_server() -> (PathBuf, RemoteKeystore) {
+		let path = std::env::temp_dir().join(format!(
+			"node-template-keystore-{}-{}.sock",
+			std::process::id(),
+			std::time::SystemTime::now()
+				.duration_since(std::time::UNIX_EPOCH)
+				.unwrap()
+				.as_nanos()
+		));
+		let listener = UnixListener::bind(&path).unwrap();
+		std::thread::spawn(move || serve(Arc::new(LocalKeystore::in_memory()), listener));
+
+		let keystore = RemoteKeystore::open(&format!("unix://{}", path.display())).unwrap();
+		(path, keystore)
+	}
+
+	#[test]
+	fn signs_through_the_server() {
+		let (path, keystore) = start_server();
+
+		let public =
+			SyncCryptoStore::sr25519_generate_new(&keystore, AURA, Some("//Alice")).unwrap();
+		assert_eq!(public, sr25519::Pair::from_string("//Alice", None).unwrap().public());
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, AURA), vec![public]);
+		assert!(SyncCryptoStore::has_keys(&keystore, &[(public.0.to_vec(), AURA)]));
+
+		let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, public.0.to_vec());
+		let signature =
+			SyncCryptoStore::sign_with(&keystore, AURA, &key, b"block").unwrap().unwrap();
+		let signature = sr25519::Signature::try_from(&signature[..]).unwrap();
+		assert!(sr25519::Pair::verify(&signature, b"block", &public));
+
+		let missing = CryptoTypePublicPair(sr25519::CRYPTO_ID, vec![0; 32]);
+		assert_eq!(SyncCryptoStore::sign_with(&keystore, AURA, &missing, b"block").unwrap(), None);
+
+		std::fs::remove_file(path).unwrap();
+	}
+}
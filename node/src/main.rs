@@ -2,10 +2,12 @@
 #![warn(missing_docs)]
 
 mod chain_spec;
+mod check_weights;
 #[macro_use]
 mod service;
 mod cli;
 mod command;
+mod remote_keystore;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
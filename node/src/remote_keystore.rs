@@ -0,0 +1,436 @@
+//! A keystore that keeps its keys in another process and signs over a Unix socket.
+//!
+//! The node connects to the address given with `--keystore-uri unix:///path/to/socket` and sends
+//! one JSON-RPC 2.0 request per line, waiting for the matching response line before sending the
+//! next one. The library's `keystore_server` module implements the other end for the
+//! `node-template-keystore` binary.
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData},
+	CryptoStore, Error, SyncCryptoStore,
+};
+use std::{
+	io::{self, BufRead, BufReader, Write},
+	os::unix::net::UnixStream,
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Mutex,
+	},
+	time::Duration,
+};
+
+pub(crate) const LOG_TARGET: &str = "remote-keystore";
+
+/// How long the node waits on the keystore server before giving up on a request.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A keystore operation, serialized as the `method` and `params` of a request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params")]
+pub enum Call {
+	/// List the public keys of a key type.
+	#[serde(rename = "keystore_keys")]
+	Keys { key_type: String },
+	/// Generate a new key pair, optionally from a seed, and return its public key.
+	#[serde(rename = "keystore_generate")]
+	Generate { key_type: String, crypto: String, seed: Option<String> },
+	/// Insert a key pair of unknown crypto type.
+	#[serde(rename = "keystore_insert")]
+	Insert { key_type: String, suri: String, public: Bytes },
+	/// Check whether all the given keys are present.
+	#[serde(rename = "keystore_hasKeys")]
+	HasKeys { keys: Vec<(Bytes, String)> },
+	/// Sign a message, returning `null` when the key is not present.
+	#[serde(rename = "keystore_sign")]
+	Sign { key_type: String, crypto: String, public: Bytes, message: Bytes },
+	/// Sign a 32 byte pre-hashed message with an ECDSA key.
+	#[serde(rename = "keystore_signPrehashed")]
+	SignPrehashed { key_type: String, public: Bytes, message: Bytes },
+}
+
+/// A public key together with the crypto it belongs to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublicKey {
+	pub crypto: String,
+	pub public: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Request {
+	pub jsonrpc: String,
+	pub id: u64,
+	#[serde(flatten)]
+	pub call: Call,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Response {
+	pub jsonrpc: String,
+	pub id: Option<u64>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub result: Option<Value>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct RpcError {
+	pub code: i64,
+	pub message: String,
+}
+
+fn key_type_name(id: KeyTypeId) -> String {
+	String::from_utf8_lossy(&id.0).into_owned()
+}
+
+pub(crate) fn crypto_name(id: CryptoTypeId) -> String {
+	String::from_utf8_lossy(&id.0).into_owned()
+}
+
+pub(crate) fn parse_crypto(name: &str) -> Result<CryptoTypeId, String> {
+	<[u8; 4]>::try_from(name.as_bytes())
+		.map(CryptoTypeId)
+		.map_err(|_| format!("invalid crypto type `{}`", name))
+}
+
+/// Client side of the protocol, used by the node as its remote keystore.
+pub struct RemoteKeystore {
+	path: PathBuf,
+	/// The open connection, dropped after a failed request and reopened by the next one.
+	connection: Mutex<Option<BufReader<UnixStream>>>,
+	next_id: AtomicU64,
+}
+
+impl RemoteKeystore {
+	/// Connects to the keystore server listening at `uri`, which must be of the form
+	/// `unix:///path/to/socket`.
+	pub fn open(uri: &str) -> Result<Self, String> {
+		let path = uri
+			.strip_prefix("unix://")
+			.ok_or_else(|| format!("unsupported keystore URI `{}`, expected unix://<path>", uri))?;
+		let keystore =
+			Self { path: path.into(), connection: Mutex::new(None), next_id: AtomicU64::new(0) };
+		// Fail at startup rather than on the first slot if the server is not there.
+		let connection = keystore.connect().map_err(|e| format!("{}: {}", path, e))?;
+		*keystore.connection.lock().expect("not poisoned; qed") = Some(connection);
+		Ok(keystore)
+	}
+
+	fn connect(&self) -> io::Result<BufReader<UnixStream>> {
+		let stream = UnixStream::connect(&self.path)?;
+		stream.set_read_timeout(Some(TIMEOUT))?;
+		stream.set_write_timeout(Some(TIMEOUT))?;
+		Ok(BufReader::new(stream))
+	}
+
+	fn round_trip(
+		&self,
+		connection: &mut Option<BufReader<UnixStream>>,
+		request: &Request,
+	) -> io::Result<Response> {
+		if connection.is_none() {
+			*connection = Some(self.connect()?);
+		}
+		let reader = connection.as_mut().expect("connected above; qed");
+
+		let mut line = serde_json::to_vec(request)?;
+		line.push(b'\n');
+		reader.get_mut().write_all(&line)?;
+
+		let mut response = String::new();
+		if reader.read_line(&mut response)? == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"keystore server closed the connection",
+			))
+		}
+		Ok(serde_json::from_str(&response)?)
+	}
+
+	fn call<T: DeserializeOwned>(&self, call: Call) -> Result<T, Error> {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let request = Request { jsonrpc: "2.0".into(), id, call };
+
+		let mut connection = self.connection.lock().map_err(|_| Error::Unavailable)?;
+		let response = self.round_trip(&mut connection, &request).map_err(|e| {
+			*connection = None;
+			log::warn!(target: LOG_TARGET, "Request to {} failed: {}", self.path.display(), e);
+			Error::Unavailable
+		})?;
+		drop(connection);
+
+		if response.id != Some(id) {
+			return Err(Error::Other(format!(
+				"keystore server answered request {:?} instead of {}",
+				response.id, id
+			)))
+		}
+		if let Some(error) = response.error {
+			return Err(Error::Other(error.message))
+		}
+		serde_json::from_value(response.result.unwrap_or(Value::Null))
+			.map_err(|e| Error::Other(format!("malformed keystore response: {}", e)))
+	}
+
+	fn public_keys<P: for<'a> TryFrom<&'a [u8]>>(
+		&self,
+		id: KeyTypeId,
+		crypto: CryptoTypeId,
+	) -> Vec<P> {
+		match SyncCryptoStore::keys(self, id) {
+			Ok(keys) => keys
+				.into_iter()
+				.filter(|key| key.0 == crypto)
+				.filter_map(|key| P::try_from(&key.1[..]).ok())
+				.collect(),
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Failed to list {} keys: {}", key_type_name(id), e);
+				Vec::new()
+			},
+		}
+	}
+
+	fn generate_new<P: for<'a> TryFrom<&'a [u8]>>(
+		&self,
+		id: KeyTypeId,
+		crypto: CryptoTypeId,
+		seed: Option<&str>,
+	) -> Result<P, Error> {
+		let public: Bytes = self.call(Call::Generate {
+			key_type: key_type_name(id),
+			crypto: crypto_name(crypto),
+			seed: seed.map(Into::into),
+		})?;
+		P::try_from(&public[..])
+			.map_err(|_| Error::ValidationError("malformed public key from keystore server".into()))
+	}
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(id, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		self.generate_new(id, sr25519::CRYPTO_ID, seed)
+	}
+
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(id, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		self.generate_new(id, ed25519::CRYPTO_ID, seed)
+	}
+
+	fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(id, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		self.generate_new(id, ecdsa::CRYPTO_ID, seed)
+	}
+
+	fn insert_unknown(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		self.call(Call::Insert {
+			key_type: key_type_name(key_type),
+			suri: suri.into(),
+			public: public.to_vec().into(),
+		})
+		.map_err(|e| log::warn!(target: LOG_TARGET, "Failed to insert key: {}", e))
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let available = SyncCryptoStore::keys(self, id)?;
+		Ok(keys.into_iter().filter(|key| available.contains(key)).collect())
+	}
+
+	fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let keys: Vec<PublicKey> = self.call(Call::Keys { key_type: key_type_name(id) })?;
+		keys.into_iter()
+			.map(|key| {
+				let crypto = parse_crypto(&key.crypto).map_err(Error::ValidationError)?;
+				Ok(CryptoTypePublicPair(crypto, key.public.0))
+			})
+			.collect()
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys
+			.iter()
+			.map(|(public, id)| (public.clone().into(), key_type_name(*id)))
+			.collect();
+		self.call(Call::HasKeys { keys }).unwrap_or_else(|e| {
+			log::warn!(target: LOG_TARGET, "Failed to look up keys: {}", e);
+			false
+		})
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		let signature: Option<Bytes> = self.call(Call::Sign {
+			key_type: key_type_name(id),
+			crypto: crypto_name(key.0),
+			public: key.1.clone().into(),
+			message: msg.to_vec().into(),
+		})?;
+		Ok(signature.map(|signature| signature.0))
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		// Neither Aura nor GRANDPA need VRFs, so the protocol does not carry transcripts.
+		Err(Error::Other("VRF signing is not supported by the remote keystore".into()))
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		let signature: Option<Bytes> = self.call(Call::SignPrehashed {
+			key_type: key_type_name(id),
+			public: public.0.to_vec().into(),
+			message: msg.to_vec().into(),
+		})?;
+		signature
+			.map(|signature| {
+				ecdsa::Signature::try_from(&signature[..]).map_err(|_| {
+					Error::ValidationError("malformed signature from keystore server".into())
+				})
+			})
+			.transpose()
+	}
+}
+
+// Requests are short round trips to a local socket, so the async interface simply blocks like
+// `LocalKeystore` does for its file system accesses.
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		SyncCryptoStore::sr25519_public_keys(self, id)
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		SyncCryptoStore::sr25519_generate_new(self, id, seed)
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		SyncCryptoStore::ed25519_public_keys(self, id)
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		SyncCryptoStore::ed25519_generate_new(self, id, seed)
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		SyncCryptoStore::ecdsa_public_keys(self, id)
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		SyncCryptoStore::ecdsa_generate_new(self, id, seed)
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		SyncCryptoStore::insert_unknown(self, id, suri, public)
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		SyncCryptoStore::supported_keys(self, id, keys)
+	}
+
+	async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		SyncCryptoStore::keys(self, id)
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		SyncCryptoStore::has_keys(self, public_keys)
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		SyncCryptoStore::sign_with(self, id, key, msg)
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		SyncCryptoStore::sr25519_vrf_sign(self, key_type, public, transcript_data)
+	}
+
+	async fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		SyncCryptoStore::ecdsa_sign_prehashed(self, id, public, msg)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_unknown_schemes() {
+		assert!(RemoteKeystore::open("https://keystore.example").is_err());
+		assert!(RemoteKeystore::open("unix:///nonexistent/keystore.sock").is_err());
+	}
+}
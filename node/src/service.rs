@@ -1,15 +1,32 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::{BlockBackend, ExecutorProvider};
+use crate::{cli::Sealing, remote_keystore::RemoteKeystore};
+use futures::{FutureExt, StreamExt};
+use node_template_runtime::{self, opaque::Block, RuntimeApi, SLOT_DURATION};
+use sc_client_api::{BlockBackend, ExecutorProvider, StorageProvider};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
-use sc_keystore::LocalKeystore;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_service::{error::Error as ServiceError, Configuration, KeystoreContainer, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sc_transaction_pool_api::TransactionPool;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use sp_core::{
+	hashing::twox_128,
+	storage::{StorageData, StorageKey},
+};
+use sp_keystore::SyncCryptoStore;
+use sp_runtime::generic::BlockId;
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -36,32 +53,13 @@ type FullClient =
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
-pub fn new_partial(
+/// Builds the client, backend and keystore shared by every way of running the node.
+fn new_client(
 	config: &Configuration,
 ) -> Result<
-	sc_service::PartialComponents<
-		FullClient,
-		FullBackend,
-		FullSelectChain,
-		sc_consensus::DefaultImportQueue<Block, FullClient>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
-		(
-			sc_finality_grandpa::GrandpaBlockImport<
-				FullBackend,
-				Block,
-				FullClient,
-				FullSelectChain,
-			>,
-			sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
-			Option<Telemetry>,
-		),
-	>,
+	(Arc<FullClient>, Arc<FullBackend>, KeystoreContainer, TaskManager, Option<Telemetry>),
 	ServiceError,
 > {
-	if config.keystore_remote.is_some() {
-		return Err(ServiceError::Other("Remote Keystores are not supported.".into()))
-	}
-
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -93,6 +91,33 @@ pub fn new_partial(
 		telemetry
 	});
 
+	Ok((client, backend, keystore_container, task_manager, telemetry))
+}
+
+pub fn new_partial(
+	config: &Configuration,
+) -> Result<
+	sc_service::PartialComponents<
+		FullClient,
+		FullBackend,
+		FullSelectChain,
+		sc_consensus::DefaultImportQueue<Block, FullClient>,
+		sc_transaction_pool::FullPool<Block, FullClient>,
+		(
+			sc_finality_grandpa::GrandpaBlockImport<
+				FullBackend,
+				Block,
+				FullClient,
+				FullSelectChain,
+			>,
+			sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+			Option<Telemetry>,
+		),
+	>,
+	ServiceError,
+> {
+	let (client, backend, keystore_container, task_manager, telemetry) = new_client(config)?;
+
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
 	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
@@ -149,11 +174,41 @@ pub fn new_partial(
 	})
 }
 
-fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
-	// FIXME: here would the concrete keystore be built,
-	//        must return a concrete type (NOT `LocalKeystore`) that
-	//        implements `CryptoStore` and `SyncCryptoStore`
-	Err("Remote Keystore not supported.")
+/// Routes signing through the keystore server given with `--keystore-uri`, if any.
+fn set_remote_keystore(
+	config: &Configuration,
+	keystore_container: &mut KeystoreContainer,
+) -> Result<(), ServiceError> {
+	if let Some(url) = &config.keystore_remote {
+		match RemoteKeystore::open(url) {
+			Ok(k) => keystore_container.set_remote_keystore(Arc::new(k)),
+			Err(e) =>
+				return Err(ServiceError::Other(format!(
+					"Error hooking up remote keystore for {}: {}",
+					url, e
+				))),
+		};
+	}
+	Ok(())
+}
+
+/// Puts a `tmpl` key for the development account, e.g. `//Alice` for `--alice` or `--dev`, into
+/// the keystore, so the template pallet's offchain worker can sign without `author_insertKey`.
+fn insert_dev_offchain_key(
+	config: &Configuration,
+	keystore_container: &KeystoreContainer,
+) -> Result<(), ServiceError> {
+	if let Some(seed) = &config.dev_key_seed {
+		SyncCryptoStore::sr25519_generate_new(
+			&*keystore_container.sync_keystore(),
+			node_template_runtime::pallet_template::KEY_TYPE,
+			Some(seed),
+		)
+		.map_err(|e| {
+			ServiceError::Other(format!("Error inserting the offchain worker key: {}", e))
+		})?;
+	}
+	Ok(())
 }
 
 /// Builds a new service for a full client.
@@ -169,16 +224,9 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		other: (block_import, grandpa_link, mut telemetry),
 	} = new_partial(&config)?;
 
-	if let Some(url) = &config.keystore_remote {
-		match remote_keystore(url) {
-			Ok(k) => keystore_container.set_remote_keystore(k),
-			Err(e) =>
-				return Err(ServiceError::Other(format!(
-					"Error hooking up remote keystore for {}: {}",
-					url, e
-				))),
-		};
-	}
+	set_remote_keystore(&config, &mut keystore_container)?;
+	insert_dev_offchain_key(&config, &keystore_container)?;
+
 	let grandpa_protocol_name = sc_finality_grandpa::protocol_standard_name(
 		&client.block_hash(0).ok().flatten().expect("Genesis block exists; qed"),
 		&config.chain_spec,
@@ -226,8 +274,12 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		let pool = transaction_pool.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: None,
+			};
 
 			Ok(crate::rpc::create_full(deps))
 		})
@@ -343,3 +395,210 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	network_starter.start_network();
 	Ok(task_manager)
 }
+
+/// Builds a new service for a full client that seals blocks on demand instead of running Aura and
+/// GRANDPA. Only meant for development and integration tests.
+pub fn new_manual_seal(
+	config: Configuration,
+	sealing: Sealing,
+) -> Result<TaskManager, ServiceError> {
+	let (client, backend, mut keystore_container, mut task_manager, mut telemetry) =
+		new_client(&config)?;
+	set_remote_keystore(&config, &mut keystore_container)?;
+	insert_dev_offchain_key(&config, &keystore_container)?;
+
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+		config.transaction_pool.clone(),
+		config.role.is_authority().into(),
+		config.prometheus_registry(),
+		task_manager.spawn_essential_handle(),
+		client.clone(),
+	);
+
+	let import_queue = sc_consensus_manual_seal::import_queue(
+		Box::new(client.clone()),
+		&task_manager.spawn_essential_handle(),
+		config.prometheus_registry(),
+	);
+
+	let (network, system_rpc_tx, network_starter) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			block_announce_validator_builder: None,
+			warp_sync: None,
+		})?;
+
+	if config.offchain_worker.enabled {
+		sc_service::build_offchain_workers(
+			&config,
+			task_manager.spawn_handle(),
+			client.clone(),
+			network.clone(),
+		);
+	}
+
+	let prometheus_registry = config.prometheus_registry().cloned();
+
+	// The RPC handlers use the sending half to ask the authorship task for new or finalized
+	// blocks, whatever the sealing mode.
+	let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let pool = transaction_pool.clone();
+
+		Box::new(move |deny_unsafe, _| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: Some(command_sink.clone()),
+			};
+
+			Ok(crate::rpc::create_full(deps))
+		})
+	};
+
+	let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		network,
+		client: client.clone(),
+		keystore: keystore_container.sync_keystore(),
+		task_manager: &mut task_manager,
+		transaction_pool: transaction_pool.clone(),
+		rpc_extensions_builder,
+		backend,
+		system_rpc_tx,
+		config,
+		telemetry: telemetry.as_mut(),
+	})?;
+
+	let seal_new_block = |create_empty| EngineCommand::SealNewBlock {
+		create_empty,
+		finalize: false,
+		parent_hash: None,
+		sender: None,
+	};
+	let commands_stream = match sealing {
+		Sealing::Manual => rpc_commands.boxed(),
+		Sealing::Instant => futures::stream::select(
+			rpc_commands,
+			transaction_pool
+				.import_notification_stream()
+				.map(move |_| seal_new_block(false)),
+		)
+		.boxed(),
+		Sealing::Interval(millis) => futures::stream::select(
+			rpc_commands,
+			futures::stream::unfold((), move |()| {
+				futures_timer::Delay::new(Duration::from_millis(millis))
+					.map(move |()| Some((seal_new_block(true), ())))
+			}),
+		)
+		.boxed(),
+	};
+
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		telemetry.as_ref().map(|x| x.handle()),
+	);
+
+	// Aura only accepts a block in a later slot than its parent, which the wall clock cannot
+	// promise when blocks are sealed back to back. Keep the timestamp at least one slot ahead of
+	// the previous block instead, starting from the best block so a restart does not go back.
+	let best_hash = client.chain_info().best_hash;
+	let stored = client.storage(&BlockId::Hash(best_hash), &StorageKey(timestamp_now_key()))?;
+	let last_timestamp = Arc::new(AtomicU64::new(decode_timestamp(stored)));
+	let create_inherent_data_providers = move |_, ()| {
+		let last_timestamp = last_timestamp.clone();
+		async move {
+			let now = SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.expect("Current time is always after unix epoch; qed")
+				.as_millis() as u64;
+			let mut timestamp = now;
+			let _ = last_timestamp.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+				timestamp = next_timestamp(now, last);
+				Some(timestamp)
+			});
+
+			Ok(sp_timestamp::InherentDataProvider::new(timestamp.into()))
+		}
+	};
+
+	let authorship_future = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client: client.clone(),
+		pool: transaction_pool,
+		commands_stream,
+		select_chain,
+		consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+		create_inherent_data_providers,
+	});
+
+	// the authorship task is considered essential, i.e. if it fails we take down the service
+	// with it.
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"manual-seal",
+		Some("block-authoring"),
+		authorship_future,
+	);
+
+	network_starter.start_network();
+	Ok(task_manager)
+}
+
+/// The storage key of `Timestamp::now`, under the pallet name `construct_runtime!` gives it.
+fn timestamp_now_key() -> Vec<u8> {
+	[twox_128(b"Timestamp"), twox_128(b"Now")].concat()
+}
+
+/// Decodes a stored `Timestamp::now`, which is zero before the first block.
+fn decode_timestamp(stored: Option<StorageData>) -> u64 {
+	stored
+		.and_then(|data| data.0.try_into().ok())
+		.map(u64::from_le_bytes)
+		.unwrap_or_default()
+}
+
+/// The timestamp of a manually sealed block: the wall clock, unless that is not a slot past the
+/// previous block's timestamp.
+fn next_timestamp(now: u64, last: u64) -> u64 {
+	now.max(last + SLOT_DURATION)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sealing_resumes_after_the_stored_timestamp() {
+		// A node restarted after sealing ahead of the wall clock.
+		let stored = 1_000 * SLOT_DURATION;
+		let last = decode_timestamp(Some(StorageData(stored.to_le_bytes().to_vec())));
+		assert_eq!(last, stored);
+
+		let now = stored - 10 * SLOT_DURATION;
+		let first = next_timestamp(now, last);
+		assert_eq!(first, stored + SLOT_DURATION);
+		assert_eq!(next_timestamp(now, first), stored + 2 * SLOT_DURATION);
+
+		// Once the clock catches up it is used as is.
+		assert_eq!(next_timestamp(stored + 5 * SLOT_DURATION, first), stored + 5 * SLOT_DURATION);
+	}
+
+	#[test]
+	fn sealing_starts_from_the_clock_on_a_new_chain() {
+		assert_eq!(decode_timestamp(None), 0);
+		assert_eq!(next_timestamp(1_650_000_000_000, 0), 1_650_000_000_000);
+	}
+}
@@ -0,0 +1,15 @@
+//! Runtime API definition for the template pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// Read access to the template pallet's storage.
+	pub trait TemplateApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// The value stored by `who`, if any.
+		fn get_something(who: AccountId) -> Option<u32>;
+	}
+}
@@ -0,0 +1,75 @@
+//! RPC interface for the template pallet.
+
+#![warn(missing_docs)]
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_template_rpc_runtime_api::TemplateApi as TemplateRuntimeApi;
+
+/// Template pallet RPC methods.
+#[rpc]
+pub trait TemplateApi<BlockHash, AccountId> {
+	/// Returns the value stored by `who`, or `null` if it has not stored one.
+	#[rpc(name = "template_getSomething")]
+	fn get_something(&self, who: AccountId, at: Option<BlockHash>) -> Result<Option<u32>>;
+}
+
+/// Provides RPC methods to query the template pallet's storage.
+pub struct Template<C, B> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Template<C, B> {
+	/// Creates a new instance of the Template RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId> TemplateApi<<Block as BlockT>::Hash, AccountId> for Template<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn get_something(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.get_something(&at, who).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query stored value.".into(),
+			data: Some(e.to_string().into()),
+		})
+	}
+}
@@ -4,16 +4,75 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Template;
+use codec::Decode;
 use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::{Currency, Get};
 use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, TrailingZeroInput};
 
 benchmarks! {
 	do_something {
-		let s in 0 .. 100;
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller.clone()), 42)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert_eq!(Something::<T>::get(&caller), Some(42));
+		assert_eq!(Deposit::<T>::get(&caller), T::StorageDeposit::get());
+	}
+
+	clear_something {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Template::<T>::do_something(RawOrigin::Signed(caller.clone()).into(), 42)?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Something::<T>::get(&caller), None);
+	}
+
+	// `cause_error` with nothing stored for the caller, failing with `NoneValue`.
+	cause_error_none_value {
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		assert_eq!(
+			Template::<T>::cause_error(RawOrigin::Signed(caller.clone()).into()),
+			Err(Error::<T>::NoneValue.into())
+		);
+	}
+	verify {
+		assert_eq!(Something::<T>::get(&caller), None);
+	}
+
+	// `cause_error` incrementing the caller's value.
+	cause_error_increment {
+		let caller: T::AccountId = whitelisted_caller();
+		Something::<T>::insert(&caller, 1);
+	}: cause_error(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Something::<T>::get(&caller), Some(2));
+	}
+
+	// The signature is checked by `ValidateUnsigned` before dispatch, so any will do.
+	submit_total_unsigned_with_signed_payload {
+		let public =
+			T::Public::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed");
+		let payload = TotalPayload { block_number: 1u32.into(), total: 42, public };
+		let signature =
+			T::Signature::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed");
+	}: _(RawOrigin::None, payload, signature)
+	verify {
+		assert_eq!(Total::<T>::get(), Some(42));
+	}
+
+	// The signature is checked by `ValidateUnsigned` before dispatch, so any will do.
+	submit_price_unsigned_with_signed_payload {
+		let public =
+			T::Public::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed");
+		let payload = PricePayload { block_number: 1u32.into(), price: 1_234, public };
+		let signature =
+			T::Signature::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed");
+	}: _(RawOrigin::None, payload, signature)
+	verify {
+		assert_eq!(Price::<T>::get(), Some(1_234));
 	}
 
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
@@ -14,20 +14,134 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
+pub mod oracle;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use sp_runtime::KeyTypeId;
+
+/// The key type of the accounts the offchain worker signs its submissions with.
+///
+/// Nodes need an sr25519 key of this type in their keystore to submit, e.g. through
+/// `author_insertKey`. Development nodes insert the key of their dev account themselves.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// The crypto the offchain worker signs its submissions with.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs with the `tmpl` sr25519 key in the keystore, for runtimes using `MultiSignature`.
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::*;
+	use frame_support::{
+		log,
+		pallet_prelude::*,
+		sp_std,
+		traits::{Contains, Currency, ReservableCurrency},
+	};
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendUnsignedTransaction, SignedPayload, Signer,
+			SigningTypes,
+		},
+		pallet_prelude::*,
+	};
+	use sp_runtime::traits::{IdentifyAccount, Saturating, UniqueSaturatedInto};
+
+	use crate::{oracle, WeightInfo};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// What the offchain worker signs when submitting a total.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct TotalPayload<Public, BlockNumber> {
+		/// The block the total was computed at.
+		pub block_number: BlockNumber,
+		/// The sum of every account's value at that block.
+		pub total: u32,
+		/// The key the payload is signed with.
+		pub public: Public,
+	}
+
+	impl<T: SigningTypes> SignedPayload<T> for TotalPayload<T::Public, T::BlockNumber> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// What the offchain worker signs when submitting a price.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct PricePayload<Public, BlockNumber> {
+		/// The block the price was fetched at.
+		pub block_number: BlockNumber,
+		/// The median of the latest prices fetched, in cents.
+		pub price: u32,
+		/// The key the payload is signed with.
+		pub public: Public,
+	}
+
+	impl<T: SigningTypes> SignedPayload<T> for PricePayload<T::Public, T::BlockNumber> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency in which storage deposits are held.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The amount reserved from an account while it has a value in storage.
+		#[pallet::constant]
+		type StorageDeposit: Get<BalanceOf<Self>>;
+
+		/// The keys the offchain worker signs its submissions with.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// The accounts whose offchain workers may submit a total.
+		type Submitters: Contains<Self::AccountId>;
+
+		/// Blocks to wait after a total or a price was stored before the next one is accepted. A
+		/// submission stays valid in the transaction pool for as long.
+		#[pallet::constant]
+		type UnsignedInterval: Get<Self::BlockNumber>;
+
+		/// The priority of the offchain worker's unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version. Version 0 stored a single `Something` for the whole chain, and
+	/// version 1 did not record the deposit reserved for each value.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// The pallet's runtime storage items.
@@ -36,7 +150,68 @@ pub mod pallet {
 	#[pallet::getter(fn something)]
 	// Learn more about declaring storage items:
 	// https://docs.substrate.io/v3/runtime/storage#declaring-storage-items
-	pub type Something<T> = StorageValue<_, u32>;
+	// Every account keeps its own value, so concurrent users never overwrite each other. Chains
+	// started before storage version 1 move their old value with `migrations::v1`.
+	pub type Something<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+
+	/// The deposit reserved for each account's value, returned in full when the value is cleared
+	/// whatever `StorageDeposit` is by then.
+	#[pallet::storage]
+	#[pallet::getter(fn deposit_of)]
+	pub type Deposit<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The sum of every account's value, as last computed by an offchain worker.
+	#[pallet::storage]
+	#[pallet::getter(fn total)]
+	pub type Total<T: Config> = StorageValue<_, u32>;
+
+	/// The first block at which another total is accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn next_unsigned_at)]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The price read from the oracle endpoint, in cents, as last submitted by an offchain
+	/// worker.
+	#[pallet::storage]
+	#[pallet::getter(fn price)]
+	pub type Price<T: Config> = StorageValue<_, u32>;
+
+	/// The first block at which another price is accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn next_price_at)]
+	pub type NextPriceAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Values stored for accounts from genesis on. Each account has `StorageDeposit` reserved
+		/// as if it had called `do_something` itself.
+		pub initial_values: Vec<(T::AccountId, u32)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { initial_values: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (who, value) in &self.initial_values {
+				assert!(
+					!<Something<T>>::contains_key(who),
+					"Initial values must be unique per account"
+				);
+				let deposit = T::StorageDeposit::get();
+				T::Currency::reserve(who, deposit)
+					.expect("Accounts with initial values must be able to pay the storage deposit");
+				<Something<T>>::insert(who, value);
+				<Deposit<T>>::insert(who, deposit);
+			}
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/v3/runtime/events-and-errors
@@ -44,8 +219,14 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// Event documentation should end with an array that provides descriptive names for event
-		/// parameters. [something, who]
-		SomethingStored(u32, T::AccountId),
+		/// parameters. [who, old, new]
+		SomethingStored(T::AccountId, Option<u32>, u32),
+		/// An account removed its stored value. [who, old]
+		SomethingCleared(T::AccountId, u32),
+		/// An offchain worker submitted the sum of all values. [submitter, total]
+		TotalSubmitted(T::AccountId, u32),
+		/// An offchain worker submitted the median price it fetched. [submitter, price]
+		PriceSubmitted(T::AccountId, u32),
 	}
 
 	// Errors inform users that something went wrong.
@@ -57,46 +238,249 @@ pub mod pallet {
 		StorageOverflow,
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Adds up every account's value, which is too expensive to do on chain, and submits the
+		/// total once the last one is `UnsignedInterval` blocks old.
+		///
+		/// Nodes given a price endpoint also fetch a price every block, and submit the median of
+		/// the latest ones on the same schedule.
+		fn offchain_worker(block_number: T::BlockNumber) {
+			if let Err(e) = Self::submit_total(block_number) {
+				log::debug!(target: "runtime::template", "No total submitted: {}", e);
+			}
+			if let Err(e) = Self::submit_price(block_number) {
+				log::debug!(target: "runtime::template", "No price submitted: {}", e);
+			}
+		}
+	}
+
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
-		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		/// the caller's storage entry and emits an event. This function must be dispatched by a
+		/// signed extrinsic.
+		///
+		/// Creating an entry reserves `StorageDeposit` from the caller until it is cleared.
+		#[pallet::weight(T::WeightInfo::do_something())]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/v3/runtime/origins
 			let who = ensure_signed(origin)?;
 
-			// Update storage.
-			<Something<T>>::put(something);
+			// Update storage, keeping hold of the value it replaces. The deposit is only taken
+			// when a new entry is created; overwriting an existing entry is covered already.
+			let old = <Something<T>>::try_mutate(&who, |value| -> Result<_, DispatchError> {
+				if value.is_none() {
+					let deposit = T::StorageDeposit::get();
+					T::Currency::reserve(&who, deposit)?;
+					<Deposit<T>>::insert(&who, deposit);
+				}
+				Ok(value.replace(something))
+			})?;
 
 			// Emit an event.
-			Self::deposit_event(Event::SomethingStored(something, who));
+			Self::deposit_event(Event::SomethingStored(who, old, something));
 			// Return a successful DispatchResultWithPostInfo
 			Ok(())
 		}
 
+		/// Remove the caller's storage entry and return the deposit reserved when it was created.
+		///
+		/// Freeing up storage is something we want to encourage, so a successful call is free.
+		#[pallet::weight(T::WeightInfo::clear_something())]
+		pub fn clear_something(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let old = <Something<T>>::take(&who).ok_or(Error::<T>::NoneValue)?;
+			T::Currency::unreserve(&who, <Deposit<T>>::take(&who));
+
+			Self::deposit_event(Event::SomethingCleared(who, old));
+			Ok(Pays::No.into())
+		}
+
 		/// An example dispatchable that may throw a custom error.
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+		///
+		/// Charged for the more expensive of its two branches, the successful increment.
+		#[pallet::weight(
+			T::WeightInfo::cause_error_none_value().max(T::WeightInfo::cause_error_increment())
+		)]
 		pub fn cause_error(origin: OriginFor<T>) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
 
 			// Read a value from storage.
-			match <Something<T>>::get() {
+			match <Something<T>>::get(&who) {
 				// Return an error if the value has not been set.
 				None => Err(Error::<T>::NoneValue)?,
 				Some(old) => {
 					// Increment the value read from storage; will error in the event of overflow.
 					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
 					// Update the value in storage with the incremented result.
-					<Something<T>>::put(new);
+					<Something<T>>::insert(&who, new);
 					Ok(())
 				},
 			}
 		}
+
+		/// Store the total an offchain worker computed.
+		///
+		/// Sent as an unsigned transaction; `ValidateUnsigned` checks that one of the
+		/// `Submitters` signed the payload and that a new total is due.
+		#[pallet::weight(T::WeightInfo::submit_total_unsigned_with_signed_payload())]
+		pub fn submit_total_unsigned_with_signed_payload(
+			origin: OriginFor<T>,
+			payload: TotalPayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			<Total<T>>::put(payload.total);
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			<NextUnsignedAt<T>>::put(current_block.saturating_add(T::UnsignedInterval::get()));
+
+			Self::deposit_event(Event::TotalSubmitted(
+				payload.public.into_account(),
+				payload.total,
+			));
+			Ok(())
+		}
+
+		/// Store the price an offchain worker fetched.
+		///
+		/// Sent as an unsigned transaction; `ValidateUnsigned` checks that one of the
+		/// `Submitters` signed the payload and that a new price is due.
+		#[pallet::weight(T::WeightInfo::submit_price_unsigned_with_signed_payload())]
+		pub fn submit_price_unsigned_with_signed_payload(
+			origin: OriginFor<T>,
+			payload: PricePayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			<Price<T>>::put(payload.price);
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			<NextPriceAt<T>>::put(current_block.saturating_add(T::UnsignedInterval::get()));
+
+			Self::deposit_event(Event::PriceSubmitted(
+				payload.public.into_account(),
+				payload.price,
+			));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Accepts a total or a price signed by one of the `Submitters`, computed no earlier than
+		/// the next one is due and no later than the current block.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::submit_total_unsigned_with_signed_payload { payload, signature } =>
+					Self::validate_signed_payload(
+						"TemplateOffchainWorker",
+						payload,
+						signature,
+						payload.block_number,
+						<NextUnsignedAt<T>>::get(),
+					),
+				Call::submit_price_unsigned_with_signed_payload { payload, signature } =>
+					Self::validate_signed_payload(
+						"TemplatePriceOracle",
+						payload,
+						signature,
+						payload.block_number,
+						<NextPriceAt<T>>::get(),
+					),
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Checks that `payload` was signed by one of the `Submitters` at a block between
+		/// `next_at` and the current one. Only one payload is accepted per `tag` and interval,
+		/// whoever submits it.
+		fn validate_signed_payload<P: SignedPayload<T>>(
+			tag: &'static str,
+			payload: &P,
+			signature: &T::Signature,
+			block_number: T::BlockNumber,
+			next_at: T::BlockNumber,
+		) -> TransactionValidity {
+			if !payload.verify::<T::AuthorityId>(signature.clone()) {
+				return InvalidTransaction::BadProof.into()
+			}
+			if !T::Submitters::contains(&payload.public().into_account()) {
+				return InvalidTransaction::BadSigner.into()
+			}
+			if block_number < next_at {
+				return InvalidTransaction::Stale.into()
+			}
+			if block_number > <frame_system::Pallet<T>>::block_number() {
+				return InvalidTransaction::Future.into()
+			}
+
+			ValidTransaction::with_tag_prefix(tag)
+				.priority(T::UnsignedPriority::get())
+				.and_provides(next_at)
+				.longevity(T::UnsignedInterval::get().unique_saturated_into())
+				.propagate(true)
+				.build()
+		}
+
+		/// Computes the total at `block_number` and sends it from any local `tmpl` account.
+		fn submit_total(block_number: T::BlockNumber) -> Result<(), &'static str> {
+			if block_number < <NextUnsignedAt<T>>::get() {
+				return Err("the next total is not due yet")
+			}
+
+			let total = <Something<T>>::iter_values()
+				.fold(0u32, |total, value| total.saturating_add(value));
+			let (_, result) = Signer::<T, T::AuthorityId>::any_account()
+				.send_unsigned_transaction(
+					|account| TotalPayload { block_number, total, public: account.public.clone() },
+					|payload, signature| Call::submit_total_unsigned_with_signed_payload {
+						payload,
+						signature,
+					},
+				)
+				.ok_or("no local account with a `tmpl` key")?;
+			result.map_err(|()| "unable to submit the transaction")
+		}
+
+		/// Fetches the price from this node's endpoint and, when due, sends the median of the
+		/// latest ones from any local `tmpl` account.
+		fn submit_price(block_number: T::BlockNumber) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no local account with a `tmpl` key")
+			}
+			let url = oracle::price_url().ok_or("no price endpoint configured")?;
+			let url =
+				sp_std::str::from_utf8(&url).map_err(|_| "the price endpoint is not UTF-8")?;
+
+			let samples = oracle::record_sample(oracle::fetch_price(url)?)?;
+			if block_number < <NextPriceAt<T>>::get() {
+				return Err("the next price is not due yet")
+			}
+
+			let price = oracle::median(&samples).ok_or("no price samples")?;
+			let (_, result) = signer
+				.send_unsigned_transaction(
+					|account| PricePayload { block_number, price, public: account.public.clone() },
+					|payload, signature| Call::submit_price_unsigned_with_signed_payload {
+						payload,
+						signature,
+					},
+				)
+				.ok_or("no local account with a `tmpl` key")?;
+			result.map_err(|()| "unable to submit the transaction")
+		}
 	}
 }
@@ -0,0 +1,278 @@
+//! Storage migrations, run by `Executive` when the runtime is upgraded.
+//!
+//! Every migration checks the on-chain storage version first, so it only runs once and can stay
+//! registered until a later release drops it.
+
+/// Version 1 keeps a value per account instead of a single value for the whole chain.
+pub mod v1 {
+	use crate::{Config, Deposit, Pallet, Something};
+	#[cfg(feature = "try-runtime")]
+	use frame_support::{
+		ensure, storage::migration::get_storage_value, traits::OnRuntimeUpgradeHelpersExt,
+	};
+	use frame_support::{
+		log,
+		sp_std::marker::PhantomData,
+		storage::migration::take_storage_value,
+		traits::{
+			Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, ReservableCurrency,
+			StorageVersion,
+		},
+		weights::Weight,
+	};
+
+	/// Reads the single value version 0 stored under `Something`.
+	#[cfg(feature = "try-runtime")]
+	fn legacy_value<T: Config>() -> Option<u32> {
+		get_storage_value(<Pallet<T>>::name().as_bytes(), b"Something", &[])
+	}
+
+	/// Moves the value version 0 kept for the whole chain into the per-account map, as the value
+	/// of `Owner`.
+	///
+	/// `Owner` has `StorageDeposit` reserved like any account storing a value. The legacy value is
+	/// dropped if `Owner` has a value already or can't pay the deposit.
+	pub struct MigrateToV1<T, Owner>(PhantomData<(T, Owner)>);
+
+	impl<T: Config, Owner: Get<T::AccountId>> OnRuntimeUpgrade for MigrateToV1<T, Owner> {
+		fn on_runtime_upgrade() -> Weight {
+			let db_weight = T::DbWeight::get();
+			if <Pallet<T>>::on_chain_storage_version() >= 1 {
+				log::info!(target: "runtime::template", "Storage is at version 1 already");
+				return db_weight.reads(1)
+			}
+
+			let legacy =
+				take_storage_value::<u32>(<Pallet<T>>::name().as_bytes(), b"Something", &[]);
+			if let Some(value) = legacy {
+				let owner = Owner::get();
+				if <Something<T>>::contains_key(&owner) {
+					log::warn!(
+						target: "runtime::template",
+						"Dropping legacy value {}, {:?} has a value already",
+						value,
+						owner,
+					);
+				} else if T::Currency::reserve(&owner, T::StorageDeposit::get()).is_err() {
+					log::warn!(
+						target: "runtime::template",
+						"Dropping legacy value {}, {:?} can't pay the storage deposit",
+						value,
+						owner,
+					);
+				} else {
+					<Something<T>>::insert(&owner, value);
+					<Deposit<T>>::insert(&owner, T::StorageDeposit::get());
+				}
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			db_weight.reads_writes(4, 5)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::set_temp_storage(legacy_value::<T>(), "legacy_value");
+			Self::set_temp_storage(<Something<T>>::iter().count() as u32, "entries");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(<Pallet<T>>::on_chain_storage_version() == 1, "Storage version is not 1");
+			ensure!(legacy_value::<T>().is_none(), "The legacy value was not removed");
+
+			let legacy: Option<u32> =
+				Self::get_temp_storage("legacy_value").ok_or("pre_upgrade did not run")?;
+			let before: u32 = Self::get_temp_storage("entries").ok_or("pre_upgrade did not run")?;
+			let after = <Something<T>>::iter().count() as u32;
+			// At most the legacy value was added, and nothing was lost.
+			ensure!(after >= before, "Values were lost");
+			ensure!(after - before <= legacy.is_some() as u32, "Unexpected values were added");
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::mock::{
+			new_test_ext_with_values, Balances, TemplateModule, Test, STORAGE_DEPOSIT,
+		};
+		use frame_support::{
+			storage::migration::{get_storage_value, put_storage_value},
+			traits::ConstU64,
+		};
+
+		fn put_legacy_value(value: u32) {
+			put_storage_value(b"TemplateModule", b"Something", &[], value);
+		}
+
+		fn legacy_value() -> Option<u32> {
+			get_storage_value(b"TemplateModule", b"Something", &[])
+		}
+
+		#[test]
+		fn legacy_value_moves_to_the_owner() {
+			new_test_ext_with_values(vec![(2, 5)]).execute_with(|| {
+				put_legacy_value(42);
+				assert_eq!(TemplateModule::on_chain_storage_version(), 0);
+
+				MigrateToV1::<Test, ConstU64<1>>::on_runtime_upgrade();
+
+				assert_eq!(legacy_value(), None);
+				assert_eq!(TemplateModule::something(1), Some(42));
+				assert_eq!(Balances::reserved_balance(1), STORAGE_DEPOSIT);
+				assert_eq!(TemplateModule::deposit_of(1), STORAGE_DEPOSIT);
+				// Values stored per account already are left alone.
+				assert_eq!(TemplateModule::something(2), Some(5));
+				assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+			});
+		}
+
+		#[test]
+		fn legacy_value_is_dropped_if_the_owner_cannot_take_it() {
+			// Account 1 has a value of its own, account 3 can't pay the deposit.
+			new_test_ext_with_values(vec![(1, 5)]).execute_with(|| {
+				put_legacy_value(42);
+				MigrateToV1::<Test, ConstU64<1>>::on_runtime_upgrade();
+				assert_eq!(legacy_value(), None);
+				assert_eq!(TemplateModule::something(1), Some(5));
+			});
+			new_test_ext_with_values(vec![]).execute_with(|| {
+				put_legacy_value(42);
+				MigrateToV1::<Test, ConstU64<3>>::on_runtime_upgrade();
+				assert_eq!(legacy_value(), None);
+				assert_eq!(TemplateModule::something(3), None);
+				assert_eq!(Balances::reserved_balance(3), 0);
+				assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+			});
+		}
+
+		#[test]
+		fn migration_runs_once() {
+			new_test_ext_with_values(vec![]).execute_with(|| {
+				StorageVersion::new(1).put::<TemplateModule>();
+				put_legacy_value(42);
+
+				MigrateToV1::<Test, ConstU64<1>>::on_runtime_upgrade();
+
+				assert_eq!(legacy_value(), Some(42));
+				assert_eq!(TemplateModule::something(1), None);
+			});
+		}
+
+		#[cfg(feature = "try-runtime")]
+		#[test]
+		fn upgrade_checks_pass() {
+			new_test_ext_with_values(vec![(2, 5)]).execute_with(|| {
+				put_legacy_value(42);
+				MigrateToV1::<Test, ConstU64<1>>::pre_upgrade().unwrap();
+				MigrateToV1::<Test, ConstU64<1>>::on_runtime_upgrade();
+				MigrateToV1::<Test, ConstU64<1>>::post_upgrade().unwrap();
+			});
+		}
+	}
+}
+
+/// Version 2 records the deposit reserved for each value, so the same amount is returned when
+/// it is cleared.
+pub mod v2 {
+	use crate::{BalanceOf, Config, Deposit, Pallet, Something};
+	#[cfg(feature = "try-runtime")]
+	use frame_support::ensure;
+	use frame_support::{
+		log,
+		sp_std::marker::PhantomData,
+		traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+
+	/// Records `Previous`, the deposit values stored before version 2 had reserved, for every
+	/// value without a recorded deposit.
+	pub struct MigrateToV2<T, Previous>(PhantomData<(T, Previous)>);
+
+	impl<T: Config, Previous: Get<BalanceOf<T>>> OnRuntimeUpgrade for MigrateToV2<T, Previous> {
+		fn on_runtime_upgrade() -> Weight {
+			let db_weight = T::DbWeight::get();
+			if <Pallet<T>>::on_chain_storage_version() >= 2 {
+				log::info!(target: "runtime::template", "Storage is at version 2 already");
+				return db_weight.reads(1)
+			}
+
+			let mut entries = 0u64;
+			let mut recorded = 0u64;
+			for who in <Something<T>>::iter_keys() {
+				entries += 1;
+				if !<Deposit<T>>::contains_key(&who) {
+					<Deposit<T>>::insert(&who, Previous::get());
+					recorded += 1;
+				}
+			}
+			StorageVersion::new(2).put::<Pallet<T>>();
+			log::info!(target: "runtime::template", "Recorded the deposit of {} values", recorded);
+
+			db_weight.reads_writes(1 + 2 * entries, 1 + recorded)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(<Pallet<T>>::on_chain_storage_version() == 2, "Storage version is not 2");
+			ensure!(
+				<Something<T>>::iter_keys().all(|who| <Deposit<T>>::contains_key(&who)),
+				"A value has no recorded deposit"
+			);
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::mock::{new_test_ext_with_values, TemplateModule, Test, STORAGE_DEPOSIT};
+		use frame_support::traits::ConstU64;
+
+		/// Puts storage back to how version 1 left it: values without a recorded deposit.
+		fn forget_deposits() {
+			StorageVersion::new(1).put::<TemplateModule>();
+			let _ = <Deposit<Test>>::remove_all(None);
+		}
+
+		#[test]
+		fn deposits_are_recorded_for_every_value() {
+			new_test_ext_with_values(vec![(1, 42), (2, 7)]).execute_with(|| {
+				forget_deposits();
+				<Deposit<Test>>::insert(2, STORAGE_DEPOSIT);
+
+				MigrateToV2::<Test, ConstU64<3>>::on_runtime_upgrade();
+
+				assert_eq!(TemplateModule::deposit_of(1), 3);
+				// Deposits recorded already are left alone.
+				assert_eq!(TemplateModule::deposit_of(2), STORAGE_DEPOSIT);
+				assert_eq!(TemplateModule::on_chain_storage_version(), 2);
+			});
+		}
+
+		#[test]
+		fn migration_runs_once() {
+			new_test_ext_with_values(vec![(1, 42)]).execute_with(|| {
+				forget_deposits();
+				StorageVersion::new(2).put::<TemplateModule>();
+
+				MigrateToV2::<Test, ConstU64<3>>::on_runtime_upgrade();
+
+				assert!(!<Deposit<Test>>::contains_key(1));
+			});
+		}
+
+		#[cfg(feature = "try-runtime")]
+		#[test]
+		fn upgrade_checks_pass() {
+			new_test_ext_with_values(vec![(1, 42)]).execute_with(|| {
+				forget_deposits();
+				MigrateToV2::<Test, ConstU64<3>>::on_runtime_upgrade();
+				MigrateToV2::<Test, ConstU64<3>>::post_upgrade().unwrap();
+			});
+		}
+	}
+}
@@ -0,0 +1,109 @@
+//! Reading prices from the HTTP endpoint the offchain worker is pointed at.
+//!
+//! The endpoint answers a `GET` with a JSON object holding the price as a decimal number, e.g.
+//! `{"price": 12.34}`. Prices are kept in cents.
+
+use frame_support::{
+	sp_io,
+	sp_std::{str, vec::Vec},
+};
+use lite_json::{JsonValue, NumberValue};
+use sp_runtime::offchain::{
+	http,
+	storage::{StorageRetrievalError, StorageValueRef},
+	Duration, StorageKind,
+};
+
+/// Where the node keeps the URL of the price endpoint, in its persistent offchain storage.
+///
+/// The URL is stored as raw bytes, as the `offchain_localStorageSet` RPC sets it. Nodes without
+/// one do not fetch prices.
+pub const PRICE_URL_KEY: &[u8] = b"template::price-url";
+
+/// Where the latest prices fetched are kept, in persistent offchain storage.
+pub const PRICE_SAMPLES_KEY: &[u8] = b"template::price-samples";
+
+/// How many of the latest prices the submitted median is taken over.
+pub const MAX_PRICE_SAMPLES: usize = 10;
+
+/// How long to wait for the endpoint, in milliseconds.
+const HTTP_TIMEOUT: u64 = 2_000;
+
+/// The URL of the price endpoint this node was given, if any.
+pub fn price_url() -> Option<Vec<u8>> {
+	sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, PRICE_URL_KEY)
+}
+
+/// Fetches the current price, in cents, from `url`.
+pub fn fetch_price(url: &str) -> Result<u32, &'static str> {
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT));
+	let pending = http::Request::get(url)
+		.deadline(deadline)
+		.send()
+		.map_err(|_| "unable to reach the price endpoint")?;
+	let response = pending
+		.try_wait(deadline)
+		.map_err(|_| "the price endpoint timed out")?
+		.map_err(|_| "the price request failed")?;
+	if response.code != 200 {
+		return Err("the price endpoint did not answer with 200 OK")
+	}
+
+	let body = response.body().collect::<Vec<u8>>();
+	str::from_utf8(&body)
+		.ok()
+		.and_then(parse_price)
+		.ok_or("unable to parse the price response")
+}
+
+/// Reads the price, in cents, out of a `{"price": 12.34}` response. Digits after the cents are
+/// dropped.
+pub fn parse_price(body: &str) -> Option<u32> {
+	let number = match lite_json::parse_json(body).ok()? {
+		JsonValue::Object(object) => object.into_iter().find_map(|(key, value)| match value {
+			JsonValue::Number(number) if key.iter().copied().eq("price".chars()) => Some(number),
+			_ => None,
+		})?,
+		_ => return None,
+	};
+	cents(number)
+}
+
+fn cents(number: NumberValue) -> Option<u32> {
+	if number.negative || number.exponent != 0 {
+		return None
+	}
+	let fraction = match number.fraction_length {
+		length @ 0..=2 => number.fraction.checked_mul(10u64.pow(2 - length))?,
+		length => number.fraction / 10u64.checked_pow(length - 2)?,
+	};
+	number.integer.checked_mul(100)?.checked_add(fraction)?.try_into().ok()
+}
+
+/// Adds `price` to the latest samples, dropping the oldest beyond `MAX_PRICE_SAMPLES`, and
+/// returns them.
+pub fn record_sample(price: u32) -> Result<Vec<u32>, &'static str> {
+	StorageValueRef::persistent(PRICE_SAMPLES_KEY)
+		.mutate(|samples: Result<Option<Vec<u32>>, StorageRetrievalError>| {
+			let mut samples = samples.ok().flatten().unwrap_or_default();
+			samples.push(price);
+			if samples.len() > MAX_PRICE_SAMPLES {
+				samples.remove(0);
+			}
+			Ok::<_, ()>(samples)
+		})
+		.map_err(|_| "unable to record the price sample")
+}
+
+/// The median of `samples`, or the mean of the two middle ones if there is an even number.
+pub fn median(samples: &[u32]) -> Option<u32> {
+	let mut samples = samples.to_vec();
+	samples.sort_unstable();
+	let middle = samples.len() / 2;
+	match samples.len() {
+		0 => None,
+		len if len % 2 == 0 =>
+			Some(((samples[middle - 1] as u64 + samples[middle] as u64) / 2) as u32),
+		_ => Some(samples[middle]),
+	}
+}
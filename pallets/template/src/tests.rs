@@ -1,5 +1,27 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	mock::*, oracle, Call as TemplateCall, Deposit, Error, Event as TemplateEvent, PricePayload,
+	Something, TotalPayload,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Hooks, ReservableCurrency},
+	weights::Pays,
+};
+use frame_system::offchain::SignedPayload;
+use pallet_balances::Error as BalancesError;
+use sp_core::offchain::{
+	testing::{OffchainState, PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+	OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt,
+};
+use sp_runtime::{
+	offchain::storage::StorageValueRef,
+	testing::{TestSignature, UintAuthorityId},
+	traits::ValidateUnsigned,
+	transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidityError, ValidTransaction,
+	},
+};
 
 #[test]
 fn it_works_for_default_value() {
@@ -7,7 +29,96 @@ fn it_works_for_default_value() {
 		// Dispatch a signed extrinsic.
 		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
 		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::something(1), Some(42));
+	});
+}
+
+#[test]
+fn values_are_kept_per_account() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 7));
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 43));
+
+		assert_eq!(TemplateModule::something(1), Some(43));
+		assert_eq!(TemplateModule::something(2), Some(7));
+		System::assert_has_event(TemplateEvent::<Test>::SomethingStored(2, None, 7).into());
+		System::assert_last_event(TemplateEvent::<Test>::SomethingStored(1, Some(42), 43).into());
+	});
+}
+
+#[test]
+fn clear_something_removes_entry() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 7));
+
+		let info = TemplateModule::clear_something(Origin::signed(1)).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+
+		assert_eq!(TemplateModule::something(1), None);
+		assert_eq!(TemplateModule::something(2), Some(7));
+		System::assert_last_event(TemplateEvent::<Test>::SomethingCleared(1, 42).into());
+	});
+}
+
+#[test]
+fn clear_something_fails_without_value() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(TemplateModule::clear_something(Origin::signed(1)), Error::<Test>::NoneValue);
+	});
+}
+
+#[test]
+fn storing_a_value_reserves_deposit_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_eq!(Balances::reserved_balance(1), STORAGE_DEPOSIT);
+		assert_eq!(Balances::free_balance(1), 100 - STORAGE_DEPOSIT);
+		assert_eq!(TemplateModule::deposit_of(1), STORAGE_DEPOSIT);
+
+		// Overwriting an existing entry does not take a second deposit.
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 43));
+		assert_eq!(Balances::reserved_balance(1), STORAGE_DEPOSIT);
+	});
+}
+
+#[test]
+fn clearing_a_value_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(TemplateModule::deposit_of(1), 0);
+	});
+}
+
+#[test]
+fn clearing_a_value_returns_the_deposit_it_reserved() {
+	new_test_ext().execute_with(|| {
+		// A value stored while the deposit was lower than `StorageDeposit` is now.
+		assert_ok!(Balances::reserve(&1, 3));
+		Something::<Test>::insert(1, 42);
+		Deposit::<Test>::insert(1, 3);
+
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+fn storing_a_value_requires_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::do_something(Origin::signed(3), 42),
+			BalancesError::<Test>::InsufficientBalance
+		);
+		assert_eq!(TemplateModule::something(3), None);
 	});
 }
 
@@ -18,3 +129,345 @@ fn correct_error_for_none_value() {
 		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
 	});
 }
+
+#[test]
+fn cause_error_increments_callers_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::cause_error(Origin::signed(1)));
+		assert_eq!(TemplateModule::something(1), Some(43));
+		// Other accounts are still unset.
+		assert_noop!(TemplateModule::cause_error(Origin::signed(2)), Error::<Test>::NoneValue);
+	});
+}
+
+#[test]
+fn genesis_values_are_stored_with_deposit() {
+	new_test_ext_with_values(vec![(1, 42), (2, 7)]).execute_with(|| {
+		assert_eq!(TemplateModule::something(1), Some(42));
+		assert_eq!(TemplateModule::something(2), Some(7));
+		assert_eq!(Balances::reserved_balance(1), STORAGE_DEPOSIT);
+
+		// The deposit taken at genesis is returned like any other.
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Accounts with initial values must be able to pay the storage deposit")]
+fn genesis_values_require_deposit() {
+	new_test_ext_with_values(vec![(3, 42)]);
+}
+
+/// Runs `test` with the offchain extensions registered, `keys` in the keystore and the HTTP
+/// responses `prepare` expects, and returns the transactions it submitted.
+fn with_offchain_ext(
+	values: Vec<(u64, u32)>,
+	keys: Vec<u64>,
+	prepare: impl FnOnce(&mut OffchainState),
+	test: impl FnOnce(),
+) -> Vec<Extrinsic> {
+	let (offchain, offchain_state) = TestOffchainExt::new();
+	prepare(&mut offchain_state.write());
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext_with_values(values);
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	UintAuthorityId::set_all_keys(keys);
+	ext.execute_with(test);
+	let transactions = pool_state.read().transactions.clone();
+	transactions.iter().map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap()).collect()
+}
+
+fn signed_total(
+	signer: u64,
+	block_number: u64,
+	total: u32,
+) -> (TotalPayload<UintAuthorityId, u64>, TestSignature) {
+	let payload = TotalPayload { block_number, total, public: UintAuthorityId(signer) };
+	let signature = TestSignature(signer, payload.encode());
+	(payload, signature)
+}
+
+fn validate(
+	(payload, signature): (TotalPayload<UintAuthorityId, u64>, TestSignature),
+) -> Result<ValidTransaction, TransactionValidityError> {
+	let call = TemplateCall::submit_total_unsigned_with_signed_payload { payload, signature };
+	TemplateModule::validate_unsigned(TransactionSource::External, &call)
+}
+
+#[test]
+fn offchain_worker_submits_the_total_unsigned_with_a_signed_payload() {
+	let transactions = with_offchain_ext(
+		vec![(1, 42), (2, 7)],
+		vec![SUBMITTER],
+		|_| {},
+		|| {
+			TemplateModule::offchain_worker(1);
+		},
+	);
+
+	assert_eq!(transactions.len(), 1);
+	assert_eq!(transactions[0].signature, None);
+	match &transactions[0].call {
+		Call::TemplateModule(TemplateCall::submit_total_unsigned_with_signed_payload {
+			payload,
+			signature,
+		}) => {
+			assert_eq!(payload, &signed_total(SUBMITTER, 1, 49).0);
+			assert!(SignedPayload::<Test>::verify::<TestAuthId>(payload, signature.clone()));
+		},
+		call => panic!("unexpected call {:?}", call),
+	}
+}
+
+#[test]
+fn offchain_worker_waits_for_a_key_and_the_next_interval() {
+	let without_key = with_offchain_ext(
+		vec![(1, 42)],
+		vec![],
+		|_| {},
+		|| {
+			TemplateModule::offchain_worker(1);
+		},
+	);
+	assert!(without_key.is_empty());
+
+	let too_early = with_offchain_ext(
+		vec![(1, 42)],
+		vec![SUBMITTER],
+		|_| {},
+		|| {
+			crate::NextUnsignedAt::<Test>::put(UNSIGNED_INTERVAL);
+			TemplateModule::offchain_worker(UNSIGNED_INTERVAL - 1);
+		},
+	);
+	assert!(too_early.is_empty());
+}
+
+#[test]
+fn only_fresh_totals_signed_by_submitters_are_valid() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+
+		assert_eq!(
+			validate(signed_total(SUBMITTER, 3, 42)),
+			Ok(ValidTransaction {
+				priority: UNSIGNED_PRIORITY,
+				requires: vec![],
+				provides: vec![("TemplateOffchainWorker", 0u64).encode()],
+				longevity: UNSIGNED_INTERVAL,
+				propagate: true,
+			})
+		);
+
+		let (payload, _) = signed_total(SUBMITTER, 3, 42);
+		let forged = TestSignature(SUBMITTER, signed_total(SUBMITTER, 3, 43).0.encode());
+		assert_eq!(validate((payload, forged)), InvalidTransaction::BadProof.into());
+		assert_eq!(validate(signed_total(2, 3, 42)), InvalidTransaction::BadSigner.into());
+		assert_eq!(validate(signed_total(SUBMITTER, 4, 42)), InvalidTransaction::Future.into());
+
+		crate::NextUnsignedAt::<Test>::put(3);
+		assert!(validate(signed_total(SUBMITTER, 3, 42)).is_ok());
+		assert_eq!(validate(signed_total(SUBMITTER, 2, 42)), InvalidTransaction::Stale.into());
+	});
+}
+
+#[test]
+fn submitting_a_total_stores_it_until_the_next_interval() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let (payload, signature) = signed_total(SUBMITTER, 3, 42);
+
+		assert_noop!(
+			TemplateModule::submit_total_unsigned_with_signed_payload(
+				Origin::signed(SUBMITTER),
+				payload.clone(),
+				signature.clone()
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(TemplateModule::submit_total_unsigned_with_signed_payload(
+			Origin::none(),
+			payload,
+			signature
+		));
+
+		assert_eq!(TemplateModule::total(), Some(42));
+		assert_eq!(TemplateModule::next_unsigned_at(), 3 + UNSIGNED_INTERVAL);
+		System::assert_last_event(TemplateEvent::<Test>::TotalSubmitted(SUBMITTER, 42).into());
+	});
+}
+
+const PRICE_URL: &str = "http://localhost:8000/price";
+
+fn expect_price(state: &mut OffchainState, body: &str) {
+	state.expect_request(PendingRequest {
+		method: "GET".into(),
+		uri: PRICE_URL.into(),
+		response: Some(body.as_bytes().to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+/// The prices among `transactions`.
+fn submitted_prices(transactions: &[Extrinsic]) -> Vec<PricePayload<UintAuthorityId, u64>> {
+	transactions
+		.iter()
+		.filter_map(|tx| match &tx.call {
+			Call::TemplateModule(TemplateCall::submit_price_unsigned_with_signed_payload {
+				payload,
+				signature,
+			}) => {
+				assert!(SignedPayload::<Test>::verify::<TestAuthId>(payload, signature.clone()));
+				Some(payload.clone())
+			},
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn prices_are_read_in_cents() {
+	assert_eq!(oracle::parse_price(r#"{"price": 12.34}"#), Some(1_234));
+	assert_eq!(oracle::parse_price(r#"{"symbol": "UNIT", "price": 12.5}"#), Some(1_250));
+	assert_eq!(oracle::parse_price(r#"{"price": 7}"#), Some(700));
+	assert_eq!(oracle::parse_price(r#"{"price": 0.129}"#), Some(12));
+
+	assert_eq!(oracle::parse_price(r#"{"price": -1.5}"#), None);
+	assert_eq!(oracle::parse_price(r#"{"price": "12.34"}"#), None);
+	assert_eq!(oracle::parse_price(r#"{"cost": 12.34}"#), None);
+	assert_eq!(oracle::parse_price(r#"{"price": 50000000}"#), None);
+	assert_eq!(oracle::parse_price("12.34"), None);
+}
+
+#[test]
+fn median_takes_the_middle_sample() {
+	assert_eq!(oracle::median(&[]), None);
+	assert_eq!(oracle::median(&[5]), Some(5));
+	assert_eq!(oracle::median(&[9, 1, 5]), Some(5));
+	assert_eq!(oracle::median(&[9, 1, 5, 6]), Some(5));
+	assert_eq!(oracle::median(&[u32::MAX, u32::MAX]), Some(u32::MAX));
+}
+
+fn set_price_url() {
+	sp_io::offchain::local_storage_set(
+		StorageKind::PERSISTENT,
+		oracle::PRICE_URL_KEY,
+		PRICE_URL.as_bytes(),
+	);
+}
+
+#[test]
+fn offchain_worker_submits_the_median_of_the_latest_prices() {
+	let transactions = with_offchain_ext(
+		vec![],
+		vec![SUBMITTER],
+		|state| expect_price(state, r#"{"price": 12.00}"#),
+		|| {
+			set_price_url();
+			StorageValueRef::persistent(oracle::PRICE_SAMPLES_KEY).set(&vec![1_000u32, 9_900]);
+			TemplateModule::offchain_worker(3);
+		},
+	);
+
+	assert_eq!(
+		submitted_prices(&transactions),
+		vec![PricePayload { block_number: 3, price: 1_200, public: UintAuthorityId(SUBMITTER) }]
+	);
+}
+
+#[test]
+fn offchain_worker_samples_prices_until_the_next_one_is_due() {
+	let transactions = with_offchain_ext(
+		vec![],
+		vec![SUBMITTER],
+		|state| expect_price(state, r#"{"price": 12.00}"#),
+		|| {
+			set_price_url();
+			let samples = (1..=oracle::MAX_PRICE_SAMPLES as u32).collect::<Vec<_>>();
+			StorageValueRef::persistent(oracle::PRICE_SAMPLES_KEY).set(&samples);
+			crate::NextPriceAt::<Test>::put(4);
+			TemplateModule::offchain_worker(3);
+
+			// The oldest sample made room for the new one.
+			let mut expected = samples[1..].to_vec();
+			expected.push(1_200);
+			assert_eq!(
+				StorageValueRef::persistent(oracle::PRICE_SAMPLES_KEY).get::<Vec<u32>>(),
+				Ok(Some(expected))
+			);
+		},
+	);
+
+	assert_eq!(submitted_prices(&transactions), vec![]);
+}
+
+#[test]
+fn offchain_worker_needs_a_key_and_an_endpoint_for_prices() {
+	let without_url = with_offchain_ext(
+		vec![],
+		vec![SUBMITTER],
+		|_| {},
+		|| {
+			TemplateModule::offchain_worker(1);
+		},
+	);
+	assert_eq!(submitted_prices(&without_url), vec![]);
+
+	let without_key = with_offchain_ext(
+		vec![],
+		vec![],
+		// No request is expected: the worker checks for a key before fetching.
+		|_| {},
+		|| {
+			set_price_url();
+			TemplateModule::offchain_worker(1);
+		},
+	);
+	assert_eq!(submitted_prices(&without_key), vec![]);
+}
+
+#[test]
+fn submitted_prices_are_validated_and_stored_like_totals() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let payload = PricePayload { block_number: 3, price: 1_234, public: UintAuthorityId(2) };
+		let signature = TestSignature(2, payload.encode());
+		let call = TemplateCall::submit_price_unsigned_with_signed_payload {
+			payload: payload.clone(),
+			signature: signature.clone(),
+		};
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+
+		let payload = PricePayload { public: UintAuthorityId(SUBMITTER), ..payload };
+		let signature = TestSignature(SUBMITTER, payload.encode());
+		let call = TemplateCall::submit_price_unsigned_with_signed_payload {
+			payload: payload.clone(),
+			signature: signature.clone(),
+		};
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call)
+				.map(|valid| valid.provides),
+			Ok(vec![("TemplatePriceOracle", 0u64).encode()])
+		);
+
+		assert_ok!(TemplateModule::submit_price_unsigned_with_signed_payload(
+			Origin::none(),
+			payload,
+			signature
+		));
+		assert_eq!(TemplateModule::price(), Some(1_234));
+		assert_eq!(TemplateModule::next_price_at(), 3 + UNSIGNED_INTERVAL);
+		// Prices and totals are due independently.
+		assert_eq!(TemplateModule::next_unsigned_at(), 0);
+		System::assert_last_event(TemplateEvent::<Test>::PriceSubmitted(SUBMITTER, 1_234).into());
+	});
+}
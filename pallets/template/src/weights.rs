@@ -0,0 +1,109 @@
+//! Weights for pallet_template.
+//!
+//! These are hand-written estimates, not benchmark output. The storage reads and writes are
+//! counted from the code; the base weights are rough guesses. Replace this file with the
+//! unedited output of
+//!
+//! ```sh
+//! ./target/release/node-template benchmark --chain=dev --execution=wasm \
+//!     --wasm-execution=compiled --pallet=pallet_template --extrinsic='*' --steps=50 \
+//!     --repeat=20 --output=pallets/template/src/weights.rs
+//! ```
+//!
+//! run on reference hardware with a node built with `--features runtime-benchmarks`.
+
+use frame_support::{
+	sp_std::marker::PhantomData,
+	traits::Get,
+	weights::{constants::RocksDbWeight, RuntimeDbWeight, Weight},
+};
+
+/// Weight functions needed for pallet_template.
+pub trait WeightInfo {
+	fn do_something() -> Weight;
+	fn clear_something() -> Weight;
+	fn cause_error_none_value() -> Weight;
+	fn cause_error_increment() -> Weight;
+	fn submit_total_unsigned_with_signed_payload() -> Weight;
+	fn submit_price_unsigned_with_signed_payload() -> Weight;
+}
+
+/// Estimated weights for pallet_template, priced with the runtime's database weights.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn do_something() -> Weight {
+		estimates::do_something(T::DbWeight::get())
+	}
+	fn clear_something() -> Weight {
+		estimates::clear_something(T::DbWeight::get())
+	}
+	fn cause_error_none_value() -> Weight {
+		estimates::cause_error_none_value(T::DbWeight::get())
+	}
+	fn cause_error_increment() -> Weight {
+		estimates::cause_error_increment(T::DbWeight::get())
+	}
+	fn submit_total_unsigned_with_signed_payload() -> Weight {
+		estimates::submit_total_unsigned_with_signed_payload(T::DbWeight::get())
+	}
+	fn submit_price_unsigned_with_signed_payload() -> Weight {
+		estimates::submit_price_unsigned_with_signed_payload(T::DbWeight::get())
+	}
+}
+
+/// Estimated weights priced with RocksDB database weights, for tests.
+impl WeightInfo for () {
+	fn do_something() -> Weight {
+		estimates::do_something(RocksDbWeight::get())
+	}
+	fn clear_something() -> Weight {
+		estimates::clear_something(RocksDbWeight::get())
+	}
+	fn cause_error_none_value() -> Weight {
+		estimates::cause_error_none_value(RocksDbWeight::get())
+	}
+	fn cause_error_increment() -> Weight {
+		estimates::cause_error_increment(RocksDbWeight::get())
+	}
+	fn submit_total_unsigned_with_signed_payload() -> Weight {
+		estimates::submit_total_unsigned_with_signed_payload(RocksDbWeight::get())
+	}
+	fn submit_price_unsigned_with_signed_payload() -> Weight {
+		estimates::submit_price_unsigned_with_signed_payload(RocksDbWeight::get())
+	}
+}
+
+mod estimates {
+	use super::*;
+
+	// Storage: TemplateModule Something (r:1 w:1)
+	// Storage: TemplateModule Deposit (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	pub fn do_something(db: RuntimeDbWeight) -> Weight {
+		25_000_000_u64.saturating_add(db.reads_writes(2, 3))
+	}
+	// Storage: TemplateModule Something (r:1 w:1)
+	// Storage: TemplateModule Deposit (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	pub fn clear_something(db: RuntimeDbWeight) -> Weight {
+		24_000_000_u64.saturating_add(db.reads_writes(3, 3))
+	}
+	// Storage: TemplateModule Something (r:1 w:0)
+	pub fn cause_error_none_value(db: RuntimeDbWeight) -> Weight {
+		6_000_000_u64.saturating_add(db.reads(1))
+	}
+	// Storage: TemplateModule Something (r:1 w:1)
+	pub fn cause_error_increment(db: RuntimeDbWeight) -> Weight {
+		9_000_000_u64.saturating_add(db.reads_writes(1, 1))
+	}
+	// Storage: TemplateModule Total (r:0 w:1)
+	// Storage: TemplateModule NextUnsignedAt (r:0 w:1)
+	pub fn submit_total_unsigned_with_signed_payload(db: RuntimeDbWeight) -> Weight {
+		10_000_000_u64.saturating_add(db.writes(2))
+	}
+	// Storage: TemplateModule Price (r:0 w:1)
+	// Storage: TemplateModule NextPriceAt (r:0 w:1)
+	pub fn submit_price_unsigned_with_signed_payload(db: RuntimeDbWeight) -> Weight {
+		10_000_000_u64.saturating_add(db.writes(2))
+	}
+}
@@ -0,0 +1,154 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Lets privileged origins pause whole pallets, or single calls, without a runtime upgrade.
+///
+/// The pallet implements `Contains<Call>` and is meant to be used as the runtime's
+/// `BaseCallFilter`. Pallets in `UnpausablePallets` and this pallet itself can never be
+/// paused, which keeps the way back open. Calls made with the root origin bypass the filter
+/// altogether.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{CallMetadata, Contains, GetCallMetadata, PalletInfoAccess},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_std::prelude::*;
+
+	/// The name of a pallet as given in `construct_runtime!`.
+	pub type PalletNameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+
+	/// The name of a dispatchable function.
+	pub type CallNameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Origin allowed to pause and unpause calls.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Pallets whose calls can never be paused.
+		type UnpausablePallets: Contains<PalletNameOf<Self>>;
+
+		/// The longest pallet or call name that can be paused.
+		#[pallet::constant]
+		type MaxNameLen: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// Paused calls by pallet and call name. A `None` call name pauses the whole pallet.
+	#[pallet::storage]
+	pub type PausedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, (PalletNameOf<T>, Option<CallNameOf<T>>), ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Calls were paused; `None` stands for every call of the pallet. [pallet, call]
+		Paused(PalletNameOf<T>, Option<CallNameOf<T>>),
+		/// Paused calls can be made again. [pallet, call]
+		Unpaused(PalletNameOf<T>, Option<CallNameOf<T>>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The calls are paused already.
+		AlreadyPaused,
+		/// The calls are not paused.
+		NotPaused,
+		/// The pallet must stay usable at all times.
+		Unpausable,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause `call_name` of `pallet_name`, or every call of the pallet if no call is given.
+		///
+		/// Pausing a pallet does not lift the pauses of its single calls, which stay in place
+		/// once the pallet is unpaused.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn pause(
+			origin: OriginFor<T>,
+			pallet_name: PalletNameOf<T>,
+			call_name: Option<CallNameOf<T>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!Self::is_unpausable(&pallet_name), Error::<T>::Unpausable);
+
+			let key = (pallet_name, call_name);
+			ensure!(!PausedCalls::<T>::contains_key(&key), Error::<T>::AlreadyPaused);
+			PausedCalls::<T>::insert(&key, ());
+
+			Self::deposit_event(Event::Paused(key.0, key.1));
+			Ok(())
+		}
+
+		/// Lift a pause set with `pause` for the same pallet and call name.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn unpause(
+			origin: OriginFor<T>,
+			pallet_name: PalletNameOf<T>,
+			call_name: Option<CallNameOf<T>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let key = (pallet_name, call_name);
+			ensure!(PausedCalls::<T>::contains_key(&key), Error::<T>::NotPaused);
+			PausedCalls::<T>::remove(&key);
+
+			Self::deposit_event(Event::Unpaused(key.0, key.1));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn is_unpausable(pallet_name: &PalletNameOf<T>) -> bool {
+			pallet_name[..] == *<Self as PalletInfoAccess>::name().as_bytes() ||
+				T::UnpausablePallets::contains(pallet_name)
+		}
+
+		/// Whether `function_name` of `pallet_name` is paused, directly or with its pallet.
+		pub fn is_paused(pallet_name: &[u8], function_name: &[u8]) -> bool {
+			// Names that are too long can't have been paused.
+			let pallet_name = match PalletNameOf::<T>::try_from(pallet_name.to_vec()) {
+				Ok(name) => name,
+				Err(_) => return false,
+			};
+			if Self::is_unpausable(&pallet_name) {
+				return false
+			}
+			if PausedCalls::<T>::contains_key((pallet_name.clone(), None::<CallNameOf<T>>)) {
+				return true
+			}
+			match CallNameOf::<T>::try_from(function_name.to_vec()) {
+				Ok(function_name) =>
+					PausedCalls::<T>::contains_key((pallet_name, Some(function_name))),
+				Err(_) => false,
+			}
+		}
+	}
+
+	impl<T: Config> Contains<<T as frame_system::Config>::Call> for Pallet<T>
+	where
+		<T as frame_system::Config>::Call: GetCallMetadata,
+	{
+		fn contains(call: &<T as frame_system::Config>::Call) -> bool {
+			let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+			!Self::is_paused(pallet_name.as_bytes(), function_name.as_bytes())
+		}
+	}
+}
@@ -0,0 +1,112 @@
+use crate::{mock::*, Error, Event as TxPauseEvent, PalletNameOf};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::{traits::Dispatchable, DispatchError};
+
+fn name(name: &str) -> PalletNameOf<Test> {
+	name.as_bytes().to_vec().try_into().unwrap()
+}
+
+fn transfer() -> Call {
+	Call::Balances(pallet_balances::Call::transfer { dest: 2, value: 10 })
+}
+
+fn transfer_keep_alive() -> Call {
+	Call::Balances(pallet_balances::Call::transfer_keep_alive { dest: 2, value: 10 })
+}
+
+fn is_filtered(call: Call) -> bool {
+	call.dispatch(Origin::signed(1)).map_err(|e| e.error) ==
+		Err(frame_system::Error::<Test>::CallFiltered.into())
+}
+
+#[test]
+fn pausing_a_pallet_filters_all_of_its_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TxPause::pause(Origin::root(), name("Balances"), None));
+		System::assert_last_event(TxPauseEvent::<Test>::Paused(name("Balances"), None).into());
+
+		assert!(is_filtered(transfer()));
+		assert!(is_filtered(transfer_keep_alive()));
+		assert_ok!(
+			Call::System(frame_system::Call::remark { remark: vec![] }).dispatch(Origin::signed(1))
+		);
+
+		assert_ok!(TxPause::unpause(Origin::root(), name("Balances"), None));
+		System::assert_last_event(TxPauseEvent::<Test>::Unpaused(name("Balances"), None).into());
+		assert_ok!(transfer().dispatch(Origin::signed(1)));
+		assert_eq!(Balances::free_balance(2), 110);
+	});
+}
+
+#[test]
+fn pausing_a_call_leaves_the_rest_of_the_pallet_usable() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TxPause::pause(Origin::root(), name("Balances"), Some(name("transfer"))));
+
+		assert!(is_filtered(transfer()));
+		assert_ok!(transfer_keep_alive().dispatch(Origin::signed(1)));
+		assert_eq!(Balances::free_balance(2), 110);
+	});
+}
+
+#[test]
+fn root_bypasses_the_filter() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TxPause::pause(Origin::root(), name("Balances"), None));
+		assert_ok!(Call::Balances(pallet_balances::Call::set_balance {
+			who: 2,
+			new_free: 50,
+			new_reserved: 0
+		})
+		.dispatch(Origin::root()));
+		assert_eq!(Balances::free_balance(2), 50);
+	});
+}
+
+#[test]
+fn unpausable_pallets_cannot_be_paused() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TxPause::pause(Origin::root(), name("System"), None),
+			Error::<Test>::Unpausable
+		);
+		assert_noop!(
+			TxPause::pause(Origin::root(), name("TxPause"), Some(name("unpause"))),
+			Error::<Test>::Unpausable
+		);
+	});
+}
+
+#[test]
+fn pausing_twice_or_unpausing_unpaused_calls_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TxPause::unpause(Origin::root(), name("Balances"), None),
+			Error::<Test>::NotPaused
+		);
+		assert_ok!(TxPause::pause(Origin::root(), name("Balances"), None));
+		assert_noop!(
+			TxPause::pause(Origin::root(), name("Balances"), None),
+			Error::<Test>::AlreadyPaused
+		);
+		// A paused pallet and a paused call of it are separate pauses.
+		assert_noop!(
+			TxPause::unpause(Origin::root(), name("Balances"), Some(name("transfer"))),
+			Error::<Test>::NotPaused
+		);
+	});
+}
+
+#[test]
+fn only_pause_origin_can_pause() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TxPause::pause(Origin::signed(1), name("Balances"), None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TxPause::unpause(Origin::signed(1), name("Balances"), None),
+			DispatchError::BadOrigin
+		);
+	});
+}
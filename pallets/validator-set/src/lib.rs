@@ -0,0 +1,210 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A validator set that privileged origins can grow and shrink at runtime.
+///
+/// The pallet acts as the `SessionManager` of `pallet_session`, so changes take effect at
+/// session boundaries for every consensus engine that is driven by the session pallet. New
+/// validators must register their session keys through `Session::set_keys` before they can
+/// take part.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use pallet_session::historical::IdentificationTuple;
+	use sp_runtime::{traits::Convert, Perbill};
+	use sp_staking::{
+		offence::{DisableStrategy, OffenceDetails, OnOffenceHandler},
+		SessionIndex,
+	};
+	use sp_std::prelude::*;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_session::historical::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The validator set never shrinks below this size.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The validators the next session will be planned with.
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> = StorageValue<_, Vec<T::ValidatorId>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator joins the set from the next session on. [validator]
+		ValidatorAdded(T::ValidatorId),
+		/// A validator leaves the set at the next session. [validator]
+		ValidatorRemoved(T::ValidatorId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The validator is already part of the set.
+		AlreadyValidator,
+		/// The validator is not part of the set.
+		NotValidator,
+		/// Removing the validator would leave fewer than `MinValidators`.
+		TooFewValidators,
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub initial_validators: Vec<T::ValidatorId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { initial_validators: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (i, validator_id) in self.initial_validators.iter().enumerate() {
+				assert!(
+					!self.initial_validators[..i].contains(validator_id),
+					"Initial validators must be unique"
+				);
+			}
+
+			Validators::<T>::put(&self.initial_validators);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add a validator to the set.
+		///
+		/// The validator starts authoring from the session after next, as long as it has
+		/// registered its session keys by then.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn add_validator(origin: OriginFor<T>, validator_id: T::ValidatorId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			<Validators<T>>::try_mutate(|validators| -> DispatchResult {
+				ensure!(!validators.contains(&validator_id), Error::<T>::AlreadyValidator);
+				validators.push(validator_id.clone());
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ValidatorAdded(validator_id));
+			Ok(())
+		}
+
+		/// Remove a validator from the set.
+		///
+		/// The validator keeps authoring until the session after next.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn remove_validator(
+			origin: OriginFor<T>,
+			validator_id: T::ValidatorId,
+		) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			Self::do_remove_validator(&validator_id)?;
+
+			Self::deposit_event(Event::ValidatorRemoved(validator_id));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn do_remove_validator(validator_id: &T::ValidatorId) -> DispatchResult {
+			<Validators<T>>::try_mutate(|validators| {
+				let index = validators
+					.iter()
+					.position(|v| v == validator_id)
+					.ok_or(Error::<T>::NotValidator)?;
+				ensure!(
+					validators.len() as u32 > T::MinValidators::get(),
+					Error::<T>::TooFewValidators
+				);
+				validators.remove(index);
+				Ok(())
+			})
+		}
+	}
+
+	impl<T: Config> pallet_session::SessionManager<T::ValidatorId> for Pallet<T> {
+		fn new_session(_new_index: SessionIndex) -> Option<Vec<T::ValidatorId>> {
+			// Always report the planned set, so that the historical pallet records a root for
+			// every session that key ownership can be proven against.
+			let validators = Self::validators();
+			if validators.is_empty() {
+				None
+			} else {
+				Some(validators)
+			}
+		}
+
+		fn end_session(_end_index: SessionIndex) {}
+
+		fn start_session(_start_index: SessionIndex) {}
+	}
+
+	impl<T: Config>
+		pallet_session::historical::SessionManager<T::ValidatorId, T::FullIdentification> for Pallet<T>
+	{
+		fn new_session(
+			new_index: SessionIndex,
+		) -> Option<Vec<(T::ValidatorId, T::FullIdentification)>> {
+			<Self as pallet_session::SessionManager<_>>::new_session(new_index).map(|validators| {
+				validators
+					.into_iter()
+					.filter_map(|v| T::FullIdentificationOf::convert(v.clone()).map(|id| (v, id)))
+					.collect()
+			})
+		}
+
+		fn end_session(_end_index: SessionIndex) {}
+
+		fn start_session(_start_index: SessionIndex) {}
+	}
+
+	/// Reported validators are disabled for the rest of the session and dropped from the set
+	/// from the next session on. Governance has to add them back explicitly.
+	impl<T: Config> OnOffenceHandler<T::AccountId, IdentificationTuple<T>, Weight> for Pallet<T> {
+		fn on_offence(
+			offenders: &[OffenceDetails<T::AccountId, IdentificationTuple<T>>],
+			_slash_fraction: &[Perbill],
+			_session: SessionIndex,
+			disable_strategy: DisableStrategy,
+		) -> Weight {
+			for OffenceDetails { offender: (validator_id, _), .. } in offenders {
+				if !matches!(disable_strategy, DisableStrategy::Never) {
+					pallet_session::Pallet::<T>::disable(validator_id);
+				}
+
+				if Self::do_remove_validator(validator_id).is_ok() {
+					Self::deposit_event(Event::ValidatorRemoved(validator_id.clone()));
+				}
+			}
+
+			T::DbWeight::get()
+				.reads_writes(2 * offenders.len() as Weight, 2 * offenders.len() as Weight)
+		}
+	}
+}
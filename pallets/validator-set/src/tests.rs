@@ -0,0 +1,95 @@
+use crate::{mock::*, Error, Event as ValidatorSetEvent};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::{DispatchError, Perbill};
+use sp_staking::offence::{DisableStrategy, OffenceDetails, OnOffenceHandler};
+
+#[test]
+fn genesis_validators_are_active() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3]);
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+	});
+}
+
+#[test]
+fn only_add_remove_origin_can_change_the_set() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 4), DispatchError::BadOrigin);
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::signed(1), 3),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn added_validator_is_active_from_the_session_after_next() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+		System::assert_last_event(ValidatorSetEvent::<Test>::ValidatorAdded(4).into());
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3, 4]);
+	});
+}
+
+#[test]
+fn removed_validator_is_inactive_from_the_session_after_next() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 2));
+		System::assert_last_event(ValidatorSetEvent::<Test>::ValidatorRemoved(2).into());
+
+		Session::rotate_session();
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 3]);
+	});
+}
+
+#[test]
+fn cannot_add_twice_or_remove_unknown() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), 3),
+			Error::<Test>::AlreadyValidator
+		);
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 4),
+			Error::<Test>::NotValidator
+		);
+	});
+}
+
+#[test]
+fn cannot_shrink_below_minimum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 3));
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 2),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn offenders_are_disabled_and_removed() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		ValidatorSet::on_offence(
+			&[OffenceDetails { offender: (2, 2), reporters: vec![] }],
+			&[Perbill::from_percent(10)],
+			Session::current_index(),
+			DisableStrategy::WhenSlashed,
+		);
+
+		// Validator 2 is the second one in the active set.
+		assert_eq!(Session::disabled_validators(), vec![1]);
+		assert_eq!(ValidatorSet::validators(), vec![1, 3]);
+		System::assert_last_event(ValidatorSetEvent::<Test>::ValidatorRemoved(2).into());
+	});
+}
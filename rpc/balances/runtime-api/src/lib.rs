@@ -0,0 +1,34 @@
+//! Runtime API definition for reading account balances.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The balance of an account, broken down by what can be spent.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AccountBalance<Balance> {
+	/// The free balance, including what is locked.
+	pub free: Balance,
+	/// The balance reserved for deposits and bonds.
+	pub reserved: Balance,
+	/// The part of the free balance that locks keep from being transferred.
+	pub locked: Balance,
+	/// The part of the free balance that has yet to vest. It is locked once the vesting lock
+	/// is updated, and may be locked by other locks too.
+	pub vesting: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Read access to account balances.
+	pub trait BalancesApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The balance of `who`.
+		fn account_balance(who: AccountId) -> AccountBalance<Balance>;
+	}
+}
@@ -0,0 +1,98 @@
+//! RPC interface for reading account balances, including what is locked.
+
+#![warn(missing_docs)]
+
+use std::{convert::TryInto, sync::Arc};
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use balances_rpc_runtime_api::{AccountBalance, BalancesApi as BalancesRuntimeApi};
+
+/// Balances RPC methods. `Balance` is the runtime's balance type, which is sent as
+/// `NumberOrHex` because balances may not fit in a JSON number.
+#[rpc]
+pub trait BalancesApi<BlockHash, AccountId, Balance> {
+	/// Returns the free, reserved, locked and still vesting balance of `who`.
+	#[rpc(name = "balances_accountBalance")]
+	fn account_balance(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<AccountBalance<NumberOrHex>>;
+}
+
+/// Provides RPC methods to query account balances.
+pub struct Balances<C, B> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Balances<C, B> {
+	/// Creates a new instance of the Balances RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance> BalancesApi<<Block as BlockT>::Hash, AccountId, Balance>
+	for Balances<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: BalancesRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec,
+	Balance: Codec + TryInto<NumberOrHex>,
+{
+	fn account_balance(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<AccountBalance<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let balance = api.account_balance(&at, who).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query the account balance.".into(),
+			data: Some(e.to_string().into()),
+		})?;
+
+		// `Balance` is generic here, so the conversion can fail for wider runtime types.
+		let convert = |value: Balance| {
+			value.try_into().map_err(|_| RpcError {
+				code: ErrorCode::InvalidParams,
+				message: "Balance does not fit into a NumberOrHex.".into(),
+				data: None,
+			})
+		};
+		Ok(AccountBalance {
+			free: convert(balance.free)?,
+			reserved: convert(balance.reserved)?,
+			locked: convert(balance.locked)?,
+			vesting: convert(balance.vesting)?,
+		})
+	}
+}
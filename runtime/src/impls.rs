@@ -0,0 +1,60 @@
+//! Fee handling: how weight is priced, and where the fees go.
+
+use crate::{Authorship, Balance, Balances, ExtrinsicBaseWeight, EXTRINSIC_BASE_FEE};
+use frame_support::{
+	traits::{Currency, Imbalance, OnUnbalanced},
+	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
+};
+use sp_runtime::Perbill;
+use sp_std::marker::PhantomData;
+
+type NegativeImbalance = <Balances as Currency<crate::AccountId>>::NegativeImbalance;
+
+/// Prices weight linearly, so that `ExtrinsicBaseWeight` costs `EXTRINSIC_BASE_FEE`.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let p = EXTRINSIC_BASE_FEE;
+		let q = Balance::from(ExtrinsicBaseWeight::get());
+		smallvec::smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			negative: false,
+			coeff_frac: Perbill::from_rational(p % q, q),
+			coeff_integer: p / q,
+		}]
+	}
+}
+
+/// Credits the author of the current block. Burns the funds if the author is unknown.
+pub struct ToAuthor;
+
+impl OnUnbalanced<NegativeImbalance> for ToAuthor {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		if let Some(author) = Authorship::author() {
+			Balances::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees between `Treasury` (80%) and the block author (20%), and gives tips
+/// to the block author in full.
+pub struct DealWithFees<Treasury>(PhantomData<Treasury>);
+
+impl<Treasury> OnUnbalanced<NegativeImbalance> for DealWithFees<Treasury>
+where
+	Treasury: OnUnbalanced<NegativeImbalance>,
+{
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let (to_treasury, mut to_author) = fees.ration(80, 20);
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut to_author);
+			}
+			Treasury::on_unbalanced(to_treasury);
+			ToAuthor::on_unbalanced(to_author);
+		}
+	}
+}
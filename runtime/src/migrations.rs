@@ -0,0 +1,149 @@
+//! Migrations run by `Executive` when the runtime is upgraded.
+
+use crate::{AccountId, Council, Runtime};
+#[cfg(feature = "try-runtime")]
+use frame_support::{
+	ensure, sp_io, storage::migration::get_storage_value, traits::OnRuntimeUpgradeHelpersExt,
+};
+use frame_support::{
+	log,
+	storage::{migration::take_storage_value, unhashed},
+	traits::{Get, InitializeMembers, OnRuntimeUpgrade},
+	weights::Weight,
+	StorageHasher, Twox128,
+};
+#[cfg(feature = "try-runtime")]
+use sp_std::{vec, vec::Vec};
+
+/// Hands the powers of the sudo key over to the council and removes what is left of
+/// `pallet_sudo`.
+///
+/// A council that is still empty gets the old sudo key as its only member, who can then reach
+/// root through council motions and referenda and bring in the other members. An existing council
+/// is left as it is. Does nothing once the sudo key is gone.
+pub struct RemoveSudo;
+
+impl OnRuntimeUpgrade for RemoveSudo {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+
+		let key = match take_storage_value::<AccountId>(b"Sudo", b"Key", &[]) {
+			Some(key) => key,
+			None => return db_weight.reads(1),
+		};
+
+		if Council::members().is_empty() {
+			log::info!(target: "runtime::migrations", "Handing sudo over to council member {:?}", key);
+			<Council as InitializeMembers<AccountId>>::initialize_members(&[key]);
+		}
+		// Also drop the storage version and anything else under the pallet's prefix.
+		let _ = unhashed::kill_prefix(&Twox128::hash(b"Sudo"), None);
+
+		db_weight.reads_writes(2, 3)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let key = get_storage_value::<AccountId>(b"Sudo", b"Key", &[]);
+		// Only an empty council is handed to the key.
+		let expected_council = match key {
+			Some(key) if Council::members().is_empty() => vec![key],
+			_ => Council::members(),
+		};
+		Self::set_temp_storage(expected_council, "expected_council");
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		let expected_council: Vec<AccountId> =
+			Self::get_temp_storage("expected_council").ok_or("pre_upgrade did not run")?;
+		ensure!(Council::members() == expected_council, "The council is not as expected");
+		let prefix = Twox128::hash(b"Sudo");
+		ensure!(
+			sp_io::storage::next_key(&prefix).map_or(true, |next| !next.starts_with(&prefix)),
+			"The sudo pallet's storage was not removed"
+		);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Balances, TemplateModule, TemplateStorageDeposit, Treasury, UNIT};
+	use frame_support::{
+		storage::migration::{get_storage_value, put_storage_value},
+		traits::Currency,
+	};
+
+	fn sudo_key() -> Option<AccountId> {
+		get_storage_value(b"Sudo", b"Key", &[])
+	}
+
+	#[test]
+	fn sudo_key_becomes_the_council() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let alice = AccountId::from([1; 32]);
+			put_storage_value(b"Sudo", b"Key", &[], alice.clone());
+
+			RemoveSudo::on_runtime_upgrade();
+
+			assert_eq!(Council::members(), vec![alice.clone()]);
+			assert_eq!(sudo_key(), None);
+
+			// Running it again changes nothing.
+			RemoveSudo::on_runtime_upgrade();
+			assert_eq!(Council::members(), vec![alice]);
+		});
+	}
+
+	#[test]
+	fn existing_council_is_kept() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let alice = AccountId::from([1; 32]);
+			let bob = AccountId::from([2; 32]);
+			<Council as InitializeMembers<AccountId>>::initialize_members(&[bob.clone()]);
+			put_storage_value(b"Sudo", b"Key", &[], alice);
+
+			RemoveSudo::on_runtime_upgrade();
+
+			assert_eq!(Council::members(), vec![bob]);
+			assert_eq!(sudo_key(), None);
+		});
+	}
+
+	/// Sets up a chain that still has a sudo key and a value stored for everyone.
+	fn legacy_state() -> sp_io::TestExternalities {
+		let mut ext = sp_io::TestExternalities::default();
+		ext.execute_with(|| {
+			put_storage_value(b"Sudo", b"Key", &[], AccountId::from([1; 32]));
+			put_storage_value(b"TemplateModule", b"Something", &[], 42u32);
+			let _ = Balances::deposit_creating(&Treasury::account_id(), 10 * UNIT);
+		});
+		ext
+	}
+
+	#[test]
+	fn executive_runs_every_migration() {
+		legacy_state().execute_with(|| {
+			<crate::Migrations as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+			assert_eq!(Council::members(), vec![AccountId::from([1; 32])]);
+			assert_eq!(sudo_key(), None);
+			assert_eq!(TemplateModule::something(Treasury::account_id()), Some(42));
+			assert_eq!(
+				TemplateModule::deposit_of(Treasury::account_id()),
+				TemplateStorageDeposit::get()
+			);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn upgrade_passes_the_try_runtime_checks() {
+		legacy_state().execute_with(|| {
+			crate::Executive::try_runtime_upgrade().unwrap();
+		});
+	}
+}
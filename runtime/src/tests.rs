@@ -0,0 +1,290 @@
+use crate::*;
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, OnInitialize, OnUnbalanced, OriginTrait},
+	weights::{DispatchClass, WeightToFeePolynomial},
+};
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_core::{ed25519, sr25519, Pair};
+use sp_runtime::{
+	traits::{Convert, Dispatchable, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	Digest, DigestItem, MultiAddress, MultiSigner,
+};
+
+fn alice() -> AccountId {
+	AccountId::from([1; 32])
+}
+
+fn bob() -> AccountId {
+	AccountId::from([2; 32])
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(alice(), 1_000 * UNIT), (bob(), 1_000 * UNIT)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	// Bob is the only validator, and so the author of every block.
+	pallet_validator_set::GenesisConfig::<Runtime> { initial_validators: vec![bob()] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let keys = opaque::SessionKeys {
+		aura: sr25519::Public::from_raw([2; 32]).into(),
+		grandpa: ed25519::Public::from_raw([2; 32]).into(),
+	};
+	pallet_session::GenesisConfig::<Runtime> { keys: vec![(bob(), bob(), keys)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn do_something(something: u32) -> Call {
+	Call::TemplateModule(pallet_template::Call::do_something { something })
+}
+
+fn transfer(dest: AccountId, value: Balance) -> Call {
+	Call::Balances(BalancesCall::transfer { dest: MultiAddress::Id(dest), value })
+}
+
+#[test]
+fn batch_all_dispatches_every_call() {
+	new_test_ext().execute_with(|| {
+		let calls = vec![do_something(42), transfer(bob(), 10 * UNIT)];
+		assert_ok!(Call::Utility(pallet_utility::Call::batch_all { calls })
+			.dispatch(Origin::signed(alice())));
+
+		assert_eq!(TemplateModule::something(alice()), Some(42));
+		assert_eq!(Balances::free_balance(bob()), 1_010 * UNIT);
+	});
+}
+
+#[test]
+fn batches_respect_the_origin_filter() {
+	new_test_ext().execute_with(|| {
+		let filtered_origin = || {
+			let mut origin = Origin::signed(alice());
+			origin.add_filter(|c| !matches!(c, Call::Balances(..)));
+			origin
+		};
+		let calls = vec![do_something(42), transfer(bob(), 10 * UNIT)];
+
+		// `batch_all` fails as a whole and reverts the calls before the filtered one.
+		assert!(Call::Utility(pallet_utility::Call::batch_all { calls: calls.clone() })
+			.dispatch(filtered_origin())
+			.is_err());
+		assert_eq!(TemplateModule::something(alice()), None);
+
+		// `batch` stops at the filtered call but keeps what ran before it.
+		assert_ok!(Call::Utility(pallet_utility::Call::batch { calls }).dispatch(filtered_origin()));
+		assert_eq!(TemplateModule::something(alice()), Some(42));
+		assert_eq!(Balances::free_balance(bob()), 1_000 * UNIT);
+	});
+}
+
+#[test]
+fn proxies_cannot_smuggle_calls_into_batches() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(alice()), bob(), ProxyType::TemplateOnly, 0));
+
+		let calls = vec![do_something(42), transfer(bob(), 10 * UNIT)];
+		let batch = Call::Utility(pallet_utility::Call::batch { calls });
+		assert_ok!(Proxy::proxy(Origin::signed(bob()), alice(), None, Box::new(batch)));
+
+		assert_eq!(TemplateModule::something(alice()), Some(42));
+		assert_eq!(Balances::free_balance(bob()), 1_000 * UNIT);
+	});
+}
+
+#[test]
+fn paused_pallets_are_filtered_but_system_stays_usable() {
+	new_test_ext().execute_with(|| {
+		let name = |name: &str| name.as_bytes().to_vec().try_into().unwrap();
+		assert_ok!(TxPause::pause(Origin::root(), name("TemplateModule"), None));
+
+		assert_eq!(
+			do_something(42).dispatch(Origin::signed(alice())).map_err(|e| e.error),
+			Err(frame_system::Error::<Runtime>::CallFiltered.into())
+		);
+		assert_ok!(Call::System(frame_system::Call::remark { remark: vec![] })
+			.dispatch(Origin::signed(alice())));
+
+		// Governance must always be able to lift the pause again.
+		for pallet in ["System", "Timestamp", "TxPause", "Council", "Democracy"] {
+			assert_noop!(
+				TxPause::pause(Origin::root(), name(pallet), None),
+				pallet_tx_pause::Error::<Runtime>::Unpausable
+			);
+		}
+	});
+}
+
+/// Runs `blocks` blocks that all use `fullness` of the normal dispatch weight, and returns the
+/// fee multiplier after each of them.
+fn multiplier_trajectory(fullness: Perquintill, start: Multiplier, blocks: u32) -> Vec<Multiplier> {
+	let max_normal = BlockWeights::get().get(DispatchClass::Normal).max_total.unwrap();
+	new_test_ext().execute_with(|| {
+		System::set_block_consumed_resources(fullness * max_normal, 0);
+		let mut multiplier = start;
+		(0..blocks)
+			.map(|_| {
+				multiplier =
+					<Runtime as pallet_transaction_payment::Config>::FeeMultiplierUpdate::convert(
+						multiplier,
+					);
+				multiplier
+			})
+			.collect()
+	})
+}
+
+#[test]
+fn full_blocks_raise_fees() {
+	let trajectory = multiplier_trajectory(Perquintill::one(), Multiplier::one(), DAYS);
+	assert!(trajectory.windows(2).all(|pair| pair[0] < pair[1]));
+
+	let after_a_day = *trajectory.last().unwrap();
+	assert!(after_a_day > Multiplier::saturating_from_rational(111, 100));
+	assert!(after_a_day < Multiplier::saturating_from_rational(112, 100));
+}
+
+#[test]
+fn empty_blocks_lower_fees_down_to_the_minimum() {
+	let trajectory = multiplier_trajectory(Perquintill::zero(), Multiplier::one(), DAYS);
+	assert!(trajectory.windows(2).all(|pair| pair[0] > pair[1]));
+
+	let after_a_day = *trajectory.last().unwrap();
+	assert!(after_a_day > Multiplier::saturating_from_rational(96, 100));
+	assert!(after_a_day < Multiplier::saturating_from_rational(97, 100));
+
+	let at_minimum = multiplier_trajectory(Perquintill::zero(), MinimumMultiplier::get(), 10);
+	assert!(at_minimum.iter().all(|multiplier| *multiplier == MinimumMultiplier::get()));
+}
+
+#[test]
+fn blocks_at_the_target_keep_fees_steady() {
+	let trajectory = multiplier_trajectory(TargetBlockFullness::get(), Multiplier::one(), 100);
+	assert!(trajectory.iter().all(|multiplier| *multiplier == Multiplier::one()));
+}
+
+#[test]
+fn extrinsic_base_weight_costs_the_base_fee() {
+	assert_eq!(WeightToFee::calc(&ExtrinsicBaseWeight::get()), EXTRINSIC_BASE_FEE);
+	assert_eq!(WeightToFee::calc(&(2 * ExtrinsicBaseWeight::get())), 2 * EXTRINSIC_BASE_FEE);
+}
+
+#[test]
+fn fees_are_split_and_tips_go_to_the_author() {
+	new_test_ext().execute_with(|| {
+		// Slot 0 makes the first Aura authority, Bob, the author.
+		let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, 0u64.encode())] };
+		System::initialize(&2, &Default::default(), &digest);
+		assert_eq!(Authorship::author(), Some(bob()));
+
+		let fees = Balances::issue(100 * UNIT);
+		let tips = Balances::issue(10 * UNIT);
+		DealWithFees::<Treasury>::on_unbalanceds(vec![fees, tips].into_iter());
+
+		assert_eq!(Balances::free_balance(bob()), 1_030 * UNIT);
+		assert_eq!(Balances::free_balance(Treasury::account_id()), 80 * UNIT);
+	});
+}
+
+#[test]
+fn dust_goes_to_the_treasury() {
+	new_test_ext().execute_with(|| {
+		// The treasury's genesis makes sure its account exists.
+		let existential_deposit = Balances::minimum_balance();
+		let _ = Balances::deposit_creating(&Treasury::account_id(), existential_deposit);
+
+		let dust = existential_deposit - 1;
+		assert_ok!(Balances::transfer(
+			Origin::signed(alice()),
+			MultiAddress::Id(bob()),
+			1_000 * UNIT - dust
+		));
+
+		assert_eq!(Balances::free_balance(alice()), 0);
+		assert_eq!(Balances::free_balance(Treasury::account_id()), existential_deposit + dust);
+	});
+}
+
+#[test]
+fn approved_spends_are_paid_out_at_the_spend_period() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&Treasury::account_id(), 100 * UNIT);
+		assert_ok!(Treasury::propose_spend(
+			Origin::signed(alice()),
+			50 * UNIT,
+			MultiAddress::Id(bob())
+		));
+		assert_ok!(Treasury::approve_proposal(Origin::root(), 0));
+
+		Treasury::on_initialize(SpendPeriod::get());
+		assert_eq!(Balances::free_balance(bob()), 1_050 * UNIT);
+		assert_eq!(Balances::free_balance(alice()), 1_000 * UNIT);
+		assert_eq!(Balances::free_balance(Treasury::account_id()), 50 * UNIT);
+	});
+}
+
+#[test]
+fn account_balance_reports_what_is_still_vesting() {
+	use balances_rpc_runtime_api::{runtime_decl_for_BalancesApi::BalancesApi, AccountBalance};
+	let account_balance = <Runtime as BalancesApi<Block, AccountId, Balance>>::account_balance;
+
+	new_test_ext().execute_with(|| {
+		// 100 UNIT unlocking over 100 blocks, from block 1.
+		let schedule = pallet_vesting::VestingInfo::new(100 * UNIT, UNIT, 1);
+		assert_ok!(Vesting::vested_transfer(
+			Origin::signed(alice()),
+			MultiAddress::Id(bob()),
+			schedule
+		));
+		assert_eq!(
+			account_balance(bob()),
+			AccountBalance {
+				free: 1_100 * UNIT,
+				reserved: 0,
+				locked: 100 * UNIT,
+				vesting: 100 * UNIT
+			}
+		);
+
+		// Half of it has vested, but stays locked until the lock is updated.
+		System::set_block_number(51);
+		assert_eq!(account_balance(bob()).vesting, 50 * UNIT);
+		assert_eq!(account_balance(bob()).locked, 100 * UNIT);
+		assert_ok!(Vesting::vest(Origin::signed(bob())));
+		assert_eq!(account_balance(bob()).locked, 50 * UNIT);
+	});
+}
+
+#[test]
+fn offchain_totals_must_be_signed_by_a_validator() {
+	new_test_ext().execute_with(|| {
+		let pair = sr25519::Pair::from_seed(&[3; 32]);
+		let validate = || {
+			let payload = pallet_template::TotalPayload {
+				block_number: 1,
+				total: 42,
+				public: MultiSigner::from(pair.public()),
+			};
+			let signature = pair.sign(&payload.encode()).into();
+			let call = pallet_template::Call::submit_total_unsigned_with_signed_payload {
+				payload,
+				signature,
+			};
+			TemplateModule::validate_unsigned(TransactionSource::External, &call)
+		};
+
+		assert_eq!(validate(), Err(InvalidTransaction::BadSigner.into()));
+
+		pallet_session::Validators::<Runtime>::put(vec![AccountId::from(pair.public())]);
+		assert_eq!(validate().map(|valid| valid.priority), Ok(TemplateUnsignedPriority::get()));
+	});
+}
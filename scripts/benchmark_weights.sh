@@ -0,0 +1,42 @@
+#!/usr/bin/env bash
+# Regenerates runtime/src/weights/ from fresh benchmarks. Run it on reference hardware, on an
+# otherwise idle machine, and commit the result.
+set -e
+
+# Every pallet in the runtime's `define_benchmarks!`, except the `frame_benchmarking` baseline,
+# which has no weights of its own.
+PALLETS=(
+	frame_system
+	pallet_balances
+	pallet_collective
+	pallet_democracy
+	pallet_multisig
+	pallet_proxy
+	pallet_scheduler
+	pallet_template
+	pallet_timestamp
+	pallet_treasury
+	pallet_utility
+	pallet_vesting
+)
+
+cd "$(dirname "$0")/.."
+
+echo "*** Building the node with runtime benchmarks"
+cargo build --release --features runtime-benchmarks
+
+for pallet in "${PALLETS[@]}"; do
+	echo "*** Benchmarking $pallet"
+	./target/release/node-template benchmark \
+		--chain=dev \
+		--execution=wasm \
+		--wasm-execution=compiled \
+		--pallet="$pallet" \
+		--extrinsic='*' \
+		--steps=50 \
+		--repeat=20 \
+		--output="runtime/src/weights/$pallet.rs"
+done
+
+echo "*** Done. Add a module to runtime/src/weights/mod.rs for every new file and point the"
+echo "*** pallet's WeightInfo at it."